@@ -1,8 +1,21 @@
-use std::{future::Future, sync::Arc, time::Duration};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use refimage::GenericImageRef;
+use refimage::{
+    ColorSpace, DynamicImageOwned, DynamicImageRef, GenericImageOwned, GenericImageRef, ImageOwned,
+    ImageProps,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::{GenCam, GenCamError, GenCamResult, PollExposure};
+use crate::controls::{AnalogCtrl, ExposureCtrl, SensorCtrl};
+use crate::metadata_keys;
+use crate::{
+    GenCam, GenCamCtrl, GenCamError, GenCamResult, GenCamRoi, PollExposure, PropertyValue,
+};
 
 enum CaptureInner<'cam, C: GenCam + ?Sized> {
     InProgress(&'cam mut C),
@@ -45,6 +58,26 @@ impl<'cam, C: GenCam + ?Sized> Capturing<'cam, C> {
         }
     }
 
+    /// Capture an image like [`Capturing::capture`], but give up once `timeout` has
+    /// elapsed since this call.
+    ///
+    /// The timeout covers both the exposure and the download of the image. If it is
+    /// exceeded, the capture is cancelled and [`GenCamError::TimedOut`] is returned.
+    pub fn capture_timeout(mut self, timeout: Duration) -> GenCamResult<GenericImageRef<'cam>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.poll_once() {
+                Some(PollExposure::Ready(res)) => break res,
+                // dropping `self` cancels the capture
+                Some(_) if remaining.is_zero() => break Err(GenCamError::TimedOut),
+                Some(PollExposure::Wait(dur)) => std::thread::sleep(dur.min(remaining)),
+                Some(PollExposure::Soon) => continue,
+                None => break Err(GenCamError::AccessViolation),
+            }
+        }
+    }
+
     /// Capture an image, blocking the current task until either the capture completes,
     /// an error is returned, or a panic happens. If `self` is finished and already
     /// yielded a result once, returns an `AccessViolation` error.
@@ -145,17 +178,184 @@ pub trait Capture: GenCam {
     fn capture(&mut self) -> GenCamResult<GenericImageRef<'_>> {
         self.capture_guard()?.capture()
     }
+
+    /// Capture an image like [`Capture::capture`], and record the capture settings in the
+    /// image metadata using the keys in [`metadata_keys`]:
+    ///
+    /// - [`metadata_keys::EXPOSURE_TIME`]: the exposure time, in seconds.
+    /// - [`metadata_keys::GAIN`]: the gain ([`AnalogCtrl::Gain`]).
+    /// - [`metadata_keys::CCD_TEMP`]: the sensor temperature, in degrees Celsius.
+    /// - [`metadata_keys::BINNING_X`], [`metadata_keys::BINNING_Y`]: the binning factors.
+    /// - [`metadata_keys::INSTRUMENT`]: the camera vendor and name.
+    ///
+    /// The settings are read before the exposure starts, and settings the camera does
+    /// not report are skipped.
+    fn capture_with_metadata(&mut self) -> GenCamResult<GenericImageRef<'_>> {
+        let exposure = self.get_exposure().ok();
+        let gain = self
+            .get_property(GenCamCtrl::Analog(AnalogCtrl::Gain))
+            .ok()
+            .and_then(|(value, _)| value.as_f64());
+        let temperature = self.temperature().ok();
+        let binning = self.get_binning().ok();
+        let instrument = format!("{} {}", self.vendor(), self.camera_name());
+
+        let mut img = self.capture()?;
+        if let Some(exposure) = exposure {
+            img.insert_key(
+                metadata_keys::EXPOSURE_TIME,
+                (exposure.as_secs_f64(), "Exposure time (s)"),
+            )
+            .map_err(key_err)?;
+        }
+        if let Some(gain) = gain {
+            img.insert_key(metadata_keys::GAIN, (gain, "Gain"))
+                .map_err(key_err)?;
+        }
+        if let Some(temperature) = temperature {
+            img.insert_key(
+                metadata_keys::CCD_TEMP,
+                (temperature, "CCD temperature (C)"),
+            )
+            .map_err(key_err)?;
+        }
+        if let Some((horz, vert)) = binning {
+            img.insert_key(metadata_keys::BINNING_X, (horz, "X binning"))
+                .map_err(key_err)?;
+            img.insert_key(metadata_keys::BINNING_Y, (vert, "Y binning"))
+                .map_err(key_err)?;
+        }
+        img.insert_key(metadata_keys::INSTRUMENT, (instrument.as_str(), "Camera"))
+            .map_err(key_err)?;
+        Ok(img)
+    }
+
+    /// Capture an image, blocking the current thread until either the capture completes,
+    /// an error is returned, a panic happens, or `timeout` elapses.
+    ///
+    /// The timeout covers the whole capture, i.e. both the exposure and the download
+    /// of the image. If it is exceeded, the capture is cancelled using
+    /// [`GenCam::cancel_capture`] and [`GenCamError::TimedOut`] is returned.
+    ///
+    /// This is sugar for the corresponding method on the capture guard.
+    fn capture_with_timeout(&mut self, timeout: Duration) -> GenCamResult<GenericImageRef<'_>> {
+        self.capture_guard()?.capture_timeout(timeout)
+    }
+
+    /// Capture an image like [`Capture::capture`], and reject it if too many pixel values
+    /// are saturated, see [`FrameStats::saturated_fraction`].
+    ///
+    /// `max_saturated` is the largest acceptable fraction of saturated pixel values, in
+    /// `[0, 1]`; e.g. `0.01` rejects frames with more than 1% of saturated values.
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidValue`] if `max_saturated` is not in `[0, 1]`.
+    /// - [`GenCamError::ExposureFailed`] if the frame is saturated.
+    /// - [`GenCamError::InvalidImageType`] for floating point frames, see [`frame_stats`].
+    fn capture_checked(&mut self, max_saturated: f64) -> GenCamResult<GenericImageRef<'_>> {
+        if !(0.0..=1.0).contains(&max_saturated) {
+            return Err(GenCamError::InvalidValue(format!(
+                "Saturated fraction {max_saturated} is not in [0, 1]"
+            )));
+        }
+        let img = self.capture()?;
+        if frame_stats(&img)?.saturated_fraction > max_saturated {
+            return Err(GenCamError::ExposureFailed("saturated".into()));
+        }
+        Ok(img)
+    }
+
+    /// Capture an image like [`Capture::capture`], reversed about the X axis if `flip_x`
+    /// is set, and about the Y axis if `flip_y` is set.
+    ///
+    /// [`SensorCtrl::ReverseX`] and [`SensorCtrl::ReverseY`] are set on the camera if it
+    /// supports them, including clearing them if no flip is requested. Otherwise, the
+    /// image is flipped in software after the capture, which costs an extra copy of every
+    /// pixel. Note that flipping a Bayer image in software also mirrors its mosaic
+    /// pattern, e.g. `RGGB` becomes `GRBG` when flipped about the X axis.
+    fn capture_oriented(
+        &mut self,
+        flip_x: bool,
+        flip_y: bool,
+    ) -> GenCamResult<GenericImageRef<'_>> {
+        // flips that must be done in software
+        let mut software = [false; 2];
+        for (idx, (ctrl, flip)) in [
+            (SensorCtrl::ReverseX, flip_x),
+            (SensorCtrl::ReverseY, flip_y),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let ctrl = GenCamCtrl::Sensor(ctrl);
+            if self.list_properties().contains_key(&ctrl) {
+                self.set_property(ctrl, &PropertyValue::Bool(flip))?;
+            } else {
+                software[idx] = flip;
+            }
+        }
+        let mut img = self.capture()?;
+        let [flip_x, flip_y] = software;
+        if flip_x || flip_y {
+            let (width, height) = (img.width(), img.height());
+            match img.get_image_mut() {
+                DynamicImageRef::U8(img) => {
+                    flip_pixels(img.as_mut_slice(), width, height, flip_x, flip_y)
+                }
+                DynamicImageRef::U16(img) => {
+                    flip_pixels(img.as_mut_slice(), width, height, flip_x, flip_y)
+                }
+                DynamicImageRef::F32(img) => {
+                    flip_pixels(img.as_mut_slice(), width, height, flip_x, flip_y)
+                }
+            }
+        }
+        Ok(img)
+    }
 }
 
 impl<C: GenCam + ?Sized> Capture for C {}
 
+fn key_err(e: impl std::fmt::Display) -> GenCamError {
+    GenCamError::InvalidImageType(format!("Error inserting key: {e}"))
+}
+
+/// Flip a row-major buffer of interleaved pixels in place.
+fn flip_pixels<T>(data: &mut [T], width: usize, height: usize, flip_x: bool, flip_y: bool) {
+    if width == 0 || height == 0 {
+        return;
+    }
+    let row_len = data.len() / height;
+    let channels = row_len / width;
+    if flip_y {
+        let (top, bottom) = data.split_at_mut(row_len * (height / 2));
+        let bottom = &mut bottom[row_len * (height % 2)..];
+        for (upper, lower) in top
+            .chunks_exact_mut(row_len)
+            .zip(bottom.chunks_exact_mut(row_len).rev())
+        {
+            upper.swap_with_slice(lower);
+        }
+    }
+    if flip_x {
+        for row in data.chunks_exact_mut(row_len) {
+            // reversing the row also reverses the channels of each pixel, so
+            // restore their order afterwards
+            row.reverse();
+            if channels > 1 {
+                row.chunks_exact_mut(channels).for_each(<[T]>::reverse);
+            }
+        }
+    }
+}
+
 /// High-level extension trait for capturing frames asynchronously from a [`GenCam`].
 pub trait CaptureAsync<S: Sleep>: Capture {
     /// Starts a capture, blocking until it starts and then returns a future that blocks
     /// the current task until either the capture completes, an error is
     /// returned, or a panic happens. Before the future is returned, the
     /// current thread may be blocked waiting for the device to not be busy,
-    /// but it won't block waiting for the
+    /// but it won't block waiting for the exposure to complete.
     ///
     /// The future returned is cancellation-safe, meaning that if it is dropped before
     /// the capture completes, it will cancel the capture safely.
@@ -176,6 +376,509 @@ pub trait CaptureAsync<S: Sleep>: Capture {
 
 impl<C: GenCam + ?Sized, S: Sleep> CaptureAsync<S> for C {}
 
+/// A sequence of frames captured back-to-back with fixed settings.
+///
+/// # Example
+/// ```no_run
+/// use generic_camera::{AnyGenCam, CaptureSequence, GenericImageOwned};
+/// use std::time::Duration;
+///
+/// fn take_frames(camera: &mut AnyGenCam) -> Vec<GenericImageOwned> {
+///     let mut sequence = CaptureSequence::new(10);
+///     sequence.delay = Duration::from_secs(1);
+///     // keep the frames captured before an error
+///     sequence.run(camera.as_mut()).unwrap_or_else(|e| e.frames)
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CaptureSequence {
+    /// The number of frames to capture.
+    pub count: usize,
+    /// The delay between the end of a download and the start of the next exposure.
+    pub delay: Duration,
+    /// The region of interest to set before capturing, if any.
+    pub roi: Option<GenCamRoi>,
+}
+
+impl CaptureSequence {
+    /// Create a new sequence of `count` frames without delay between frames,
+    /// using the region of interest currently set on the camera.
+    pub fn new(count: usize) -> Self {
+        CaptureSequence {
+            count,
+            delay: Duration::ZERO,
+            roi: None,
+        }
+    }
+
+    /// Capture the sequence, blocking the current thread until all frames are captured.
+    ///
+    /// The sequence stops at the first frame that fails, including frames whose capture
+    /// is cancelled using [`GenCam::cancel_capture`] or [`crate::GenCamInfo::cancel_capture`].
+    /// In that case, the frames captured so far are returned along with the error in a
+    /// [`SequenceError`]. A cancelled frame is reported as [`GenCamError::Cancelled`].
+    pub fn run<C: GenCam + ?Sized>(
+        &self,
+        cam: &mut C,
+    ) -> Result<Vec<GenericImageOwned>, SequenceError> {
+        let mut frames = Vec::with_capacity(self.count);
+        if let Some(roi) = &self.roi
+            && let Err(error) = cam.set_roi(roi)
+        {
+            return Err(SequenceError { frames, error });
+        }
+        for idx in 0..self.count {
+            if idx > 0 && !self.delay.is_zero() {
+                std::thread::sleep(self.delay);
+            }
+            match cam.capture() {
+                Ok(img) => frames.push(img.into()),
+                Err(error) => return Err(SequenceError { frames, error }),
+            }
+        }
+        Ok(frames)
+    }
+}
+
+/// The error returned by [`CaptureSequence::run`] when the sequence stops early.
+#[derive(Error, Debug)]
+#[error("Capture sequence stopped after {} frame(s): {error}", .frames.len())]
+pub struct SequenceError {
+    /// The frames captured before the error occurred.
+    pub frames: Vec<GenericImageOwned>,
+    /// The error that stopped the sequence.
+    pub error: GenCamError,
+}
+
+/// The spacing of the exposure times in an exposure bracket, see [`bracket`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BracketSpacing {
+    /// Exposure times differ by a constant amount. This is the default.
+    #[default]
+    Linear,
+    /// Exposure times differ by a constant factor, i.e. a constant number of stops.
+    Logarithmic,
+}
+
+/// Compute the exposure times of a bracket of `steps` frames from `start` to `stop`,
+/// both included.
+///
+/// # Errors
+/// - [`GenCamError::InvalidValue`] if `steps` is less than 2, if `start` is greater than
+///   `stop`, or if `start` is zero with [`BracketSpacing::Logarithmic`].
+pub fn bracket_exposures(
+    start: Duration,
+    stop: Duration,
+    steps: usize,
+    spacing: BracketSpacing,
+) -> GenCamResult<Vec<Duration>> {
+    if steps < 2 {
+        return Err(GenCamError::InvalidValue(format!(
+            "Bracket needs at least 2 steps, got {steps}"
+        )));
+    }
+    if start > stop {
+        return Err(GenCamError::InvalidValue(format!(
+            "Bracket range is inverted: {start:?} > {stop:?}"
+        )));
+    }
+    if start.is_zero() && spacing == BracketSpacing::Logarithmic {
+        return Err(GenCamError::InvalidValue(
+            "Logarithmic bracket can not start at zero".into(),
+        ));
+    }
+    let (lo, hi) = (start.as_secs_f64(), stop.as_secs_f64());
+    Ok((0..steps)
+        .map(|idx| {
+            if idx == 0 {
+                return start;
+            } else if idx == steps - 1 {
+                return stop;
+            }
+            let frac = idx as f64 / (steps - 1) as f64;
+            Duration::from_secs_f64(match spacing {
+                BracketSpacing::Linear => lo + (hi - lo) * frac,
+                BracketSpacing::Logarithmic => lo * (hi / lo).powf(frac),
+            })
+        })
+        .collect())
+}
+
+/// Capture a bracket of `steps` frames with exposure times spaced between `start` and
+/// `stop` (see [`bracket_exposures`]), e.g. for HDR imaging.
+///
+/// The exposure time ([`crate::controls::ExposureCtrl::ExposureTime`]) is set before each
+/// frame, and the original exposure time is restored afterwards, even if a capture fails.
+///
+/// # Errors
+/// - [`GenCamError::InvalidValue`] if the bracket is invalid, see [`bracket_exposures`].
+/// - The first error encountered while capturing, or while restoring the exposure time.
+pub fn bracket<C: GenCam + ?Sized>(
+    cam: &mut C,
+    start: Duration,
+    stop: Duration,
+    steps: usize,
+    spacing: BracketSpacing,
+) -> GenCamResult<Vec<GenericImageOwned>> {
+    let exposures = bracket_exposures(start, stop, steps, spacing)?;
+    let original = cam.get_exposure()?;
+    let frames = exposures
+        .into_iter()
+        .map(|exposure| {
+            cam.set_exposure(exposure)?;
+            Ok(cam.capture()?.into())
+        })
+        .collect::<GenCamResult<Vec<_>>>();
+    let restored = cam.set_exposure(original);
+    let frames = frames?;
+    restored?;
+    Ok(frames)
+}
+
+/// Adjust the exposure time ([`ExposureCtrl::ExposureTime`]) in software until the mean
+/// pixel value of a frame is within `tolerance` of `target_mean`, for cameras without
+/// hardware auto-exposure.
+///
+/// Each iteration captures a frame, and scales the exposure time by the ratio of
+/// `target_mean` to the mean pixel value over all channels. Pixel values are not
+/// normalized, i.e. `target_mean` is in the units of the pixel type (e.g. `0..=255` for
+/// 8-bit images). The exposure time is clamped to the limits of the property (see
+/// [`GenCam::exposure_limits`]), and snapped to its step using
+/// [`Property::clamp`](crate::Property::clamp) for [`Duration`] properties.
+///
+/// Returns the exposure time that met the target. If `max_iters` frames were captured
+/// without meeting it, or the exposure time hit one of its limits, the exposure time
+/// last set is returned instead.
+///
+/// # Errors
+/// - [`GenCamError::InvalidValue`] if `target_mean` or `tolerance` is negative or not
+///   finite.
+/// - [`GenCamError::PropertyError`] if the camera has no exposure time property.
+/// - The first error encountered while capturing or setting the exposure time.
+pub fn auto_expose<C: GenCam + ?Sized>(
+    cam: &mut C,
+    target_mean: f64,
+    tolerance: f64,
+    max_iters: usize,
+) -> GenCamResult<Duration> {
+    for (name, value) in [("target mean", target_mean), ("tolerance", tolerance)] {
+        if !value.is_finite() || value < 0.0 {
+            return Err(GenCamError::InvalidValue(format!(
+                "Invalid {name} {value}: must be finite and non-negative"
+            )));
+        }
+    }
+    let (min, max) = cam.exposure_limits()?;
+    let prop = cam
+        .list_properties()
+        .get(&GenCamCtrl::Exposure(ExposureCtrl::ExposureTime))
+        .cloned();
+    let (min, max) = (min.as_secs_f64(), max.as_secs_f64());
+    let mut exposure = cam.get_exposure()?;
+    for _ in 0..max_iters {
+        let mean = image_mean(cam.capture()?.get_image());
+        if (mean - target_mean).abs() <= tolerance {
+            break;
+        }
+        let secs = if mean > 0.0 {
+            exposure.as_secs_f64() * target_mean / mean
+        } else {
+            max
+        };
+        let next = Duration::try_from_secs_f64(secs.clamp(min, max)).map_err(|e| {
+            GenCamError::InvalidValue(format!("Invalid exposure time {secs} s: {e}"))
+        })?;
+        let next = prop
+            .as_ref()
+            .and_then(|prop| prop.clamp(&next.into()).ok())
+            .and_then(|next| next.as_duration())
+            .unwrap_or(next);
+        if next == exposure {
+            break;
+        }
+        cam.set_exposure(next)?;
+        exposure = next;
+    }
+    Ok(exposure)
+}
+
+/// Compute the mean pixel value of an image over all channels.
+fn image_mean(img: &DynamicImageRef<'_>) -> f64 {
+    fn mean<T: Copy + Into<f64>>(data: &[T]) -> f64 {
+        if data.is_empty() {
+            return 0.0;
+        }
+        data.iter().map(|&v| v.into()).sum::<f64>() / data.len() as f64
+    }
+    match img {
+        DynamicImageRef::U8(img) => mean(img.as_slice()),
+        DynamicImageRef::U16(img) => mean(img.as_slice()),
+        DynamicImageRef::F32(img) => mean(img.as_slice()),
+    }
+}
+
+/// Compute a histogram of the pixel values of a frame with `bins` equally sized bins,
+/// combining all channels. See [`frame_histogram_per_channel`] for a histogram of each
+/// channel.
+///
+/// The bins span the full range of the pixel type: `0..=255` for 8-bit images,
+/// `0..=65535` for 16-bit images (regardless of the bit depth of the sensor, see
+/// [`GenCamPixelBpp`](crate::GenCamPixelBpp)), and `0.0..=1.0` for floating point images,
+/// where values outside the range are counted in the first or last bin.
+///
+/// Returns an empty histogram if `bins` is zero.
+pub fn frame_histogram(img: &GenericImageRef<'_>, bins: usize) -> Vec<u64> {
+    frame_histogram_per_channel(img, bins)
+        .into_iter()
+        .reduce(|mut hist, channel| {
+            hist.iter_mut().zip(channel).for_each(|(a, b)| *a += b);
+            hist
+        })
+        .unwrap_or_default()
+}
+
+/// Compute a histogram of the pixel values of each channel of a frame, e.g. `[R, G, B]` for
+/// an RGB image. The bins are the same as in [`frame_histogram`].
+pub fn frame_histogram_per_channel(img: &GenericImageRef<'_>, bins: usize) -> Vec<Vec<u64>> {
+    let pixels = img.width() * img.height();
+    match img.get_image() {
+        DynamicImageRef::U8(img) => histogram(img.as_slice(), pixels, bins, |v| {
+            v as usize * bins / (u8::MAX as usize + 1)
+        }),
+        DynamicImageRef::U16(img) => histogram(img.as_slice(), pixels, bins, |v| {
+            v as usize * bins / (u16::MAX as usize + 1)
+        }),
+        DynamicImageRef::F32(img) => histogram(img.as_slice(), pixels, bins, |v| {
+            (v.clamp(0.0, 1.0) * bins as f32) as usize
+        }),
+    }
+}
+
+/// Build per-channel histograms of interleaved pixel data, where `bin` maps a value to its bin.
+fn histogram<T: Copy>(
+    data: &[T],
+    pixels: usize,
+    bins: usize,
+    bin: impl Fn(T) -> usize,
+) -> Vec<Vec<u64>> {
+    if bins == 0 || pixels == 0 {
+        return Vec::new();
+    }
+    let channels = (data.len() / pixels).max(1);
+    let mut hist = vec![vec![0u64; bins]; channels];
+    for pixel in data.chunks_exact(channels) {
+        for (hist, &value) in hist.iter_mut().zip(pixel) {
+            hist[bin(value).min(bins - 1)] += 1;
+        }
+    }
+    hist
+}
+
+/// Scalar statistics of the pixel values of a frame, see [`frame_stats`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameStats {
+    /// The mean pixel value.
+    pub mean: f64,
+    /// The smallest pixel value.
+    pub min: f64,
+    /// The largest pixel value.
+    pub max: f64,
+    /// The (population) standard deviation of the pixel values.
+    pub stddev: f64,
+    /// The fraction of pixel values at the maximum of the pixel type, in `[0, 1]`.
+    pub saturated_fraction: f64,
+}
+
+/// Compute the mean, extrema, standard deviation and saturated fraction of the pixel
+/// values of a frame, combining all channels, e.g. to flag over- or under-exposed frames.
+///
+/// Pixel values are saturated at the maximum of the pixel type: 255 for 8-bit images,
+/// and 65535 for 16-bit images (regardless of the bit depth of the sensor, see
+/// [`GenCamPixelBpp`](crate::GenCamPixelBpp)). An empty frame has all statistics zero.
+///
+/// # Errors
+/// - [`GenCamError::InvalidImageType`] for floating point frames.
+pub fn frame_stats(img: &GenericImageRef<'_>) -> GenCamResult<FrameStats> {
+    match img.get_image() {
+        DynamicImageRef::U8(img) => Ok(stats(img.as_slice(), u8::MAX)),
+        DynamicImageRef::U16(img) => Ok(stats(img.as_slice(), u16::MAX)),
+        DynamicImageRef::F32(_) => Err(GenCamError::InvalidImageType(
+            "Unsupported pixel type: f32".into(),
+        )),
+    }
+}
+
+/// Compute the statistics of pixel values saturating at `saturated`.
+fn stats<T: Copy + PartialEq + Into<f64>>(data: &[T], saturated: T) -> FrameStats {
+    if data.is_empty() {
+        return FrameStats {
+            mean: 0.0,
+            min: 0.0,
+            max: 0.0,
+            stddev: 0.0,
+            saturated_fraction: 0.0,
+        };
+    }
+    let (mut sum, mut sum_sq, mut min, mut max, mut count) = (0.0, 0.0, f64::MAX, f64::MIN, 0);
+    for &value in data {
+        count += (value == saturated) as usize;
+        let value: f64 = value.into();
+        sum += value;
+        sum_sq += value * value;
+        min = min.min(value);
+        max = max.max(value);
+    }
+    let len = data.len() as f64;
+    let mean = sum / len;
+    FrameStats {
+        mean,
+        min,
+        max,
+        // rounding can make the variance slightly negative for constant frames
+        stddev: (sum_sq / len - mean * mean).max(0.0).sqrt(),
+        saturated_fraction: count as f64 / len,
+    }
+}
+
+/// The color filter array of a color sensor, named by the colors of the top left 2x2
+/// pixels in row-major order. See [`GenCam::bayer_pattern`] and [`demosaic`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BayerPattern {
+    /// Red, green / green, blue.
+    Rggb,
+    /// Blue, green / green, red.
+    Bggr,
+    /// Green, red / blue, green.
+    Grbg,
+    /// Green, blue / red, green.
+    Gbrg,
+    /// No color filter array, e.g. a monochrome sensor. This is the default.
+    #[default]
+    None,
+}
+
+impl BayerPattern {
+    /// The color space of a raw frame with this pattern, [`ColorSpace::Gray`] for
+    /// [`BayerPattern::None`].
+    pub fn color_space(&self) -> ColorSpace {
+        match self {
+            BayerPattern::Rggb => ColorSpace::Bayer(refimage::BayerPattern::Rggb),
+            BayerPattern::Bggr => ColorSpace::Bayer(refimage::BayerPattern::Bggr),
+            BayerPattern::Grbg => ColorSpace::Bayer(refimage::BayerPattern::Grbg),
+            BayerPattern::Gbrg => ColorSpace::Bayer(refimage::BayerPattern::Gbrg),
+            BayerPattern::None => ColorSpace::Gray,
+        }
+    }
+
+    /// The color (0 for red, 1 for green, 2 for blue) of each of the top left 2x2 pixels.
+    fn cfa(&self) -> Option<[[usize; 2]; 2]> {
+        match self {
+            BayerPattern::Rggb => Some([[0, 1], [1, 2]]),
+            BayerPattern::Bggr => Some([[2, 1], [1, 0]]),
+            BayerPattern::Grbg => Some([[1, 0], [2, 1]]),
+            BayerPattern::Gbrg => Some([[1, 2], [0, 1]]),
+            BayerPattern::None => None,
+        }
+    }
+}
+
+impl From<&ColorSpace> for BayerPattern {
+    /// The pattern of a [`ColorSpace::Bayer`] color space, [`BayerPattern::None`] otherwise.
+    fn from(color: &ColorSpace) -> Self {
+        match color {
+            ColorSpace::Bayer(refimage::BayerPattern::Rggb) => BayerPattern::Rggb,
+            ColorSpace::Bayer(refimage::BayerPattern::Bggr) => BayerPattern::Bggr,
+            ColorSpace::Bayer(refimage::BayerPattern::Grbg) => BayerPattern::Grbg,
+            ColorSpace::Bayer(refimage::BayerPattern::Gbrg) => BayerPattern::Gbrg,
+            _ => BayerPattern::None,
+        }
+    }
+}
+
+/// Convert a raw frame with the color filter array `pattern` to RGB, using bilinear
+/// interpolation: each missing color of a pixel is the mean of the neighbouring pixels
+/// (including diagonals) of that color. The metadata of the frame is kept.
+///
+/// This is a simple, single-threaded implementation meant for previews, which reads
+/// each pixel nine times and allocates the output frame. It does not correct for
+/// zipper or false color artifacts at edges. With [`BayerPattern::None`], the frame is
+/// returned unchanged.
+///
+/// # Errors
+/// - [`GenCamError::InvalidImageType`] for floating point frames, or frames with more
+///   than one channel.
+pub fn demosaic(
+    img: GenericImageRef<'_>,
+    pattern: BayerPattern,
+) -> GenCamResult<GenericImageOwned> {
+    let Some(cfa) = pattern.cfa() else {
+        return Ok(img.into());
+    };
+    let (width, height) = (img.width(), img.height());
+    if crate::channels_for(&img.color_space()) != Some(1) {
+        return Err(GenCamError::InvalidImageType(format!(
+            "Can not demosaic a frame with color space {:?}",
+            img.color_space()
+        )));
+    }
+    let rgb: DynamicImageOwned = match img.get_image() {
+        DynamicImageRef::U8(raw) => {
+            let data = bilinear(raw.as_slice(), width, height, cfa, |v| v as u8);
+            ImageOwned::new(data, width, height, ColorSpace::Rgb)
+                .map_err(|e| GenCamError::InvalidImageType(e.to_string()))?
+                .into()
+        }
+        DynamicImageRef::U16(raw) => {
+            let data = bilinear(raw.as_slice(), width, height, cfa, |v| v as u16);
+            ImageOwned::new(data, width, height, ColorSpace::Rgb)
+                .map_err(|e| GenCamError::InvalidImageType(e.to_string()))?
+                .into()
+        }
+        DynamicImageRef::F32(_) => {
+            return Err(GenCamError::InvalidImageType(
+                "Unsupported pixel type: f32".into(),
+            ));
+        }
+    };
+    let mut img = GenericImageOwned::from(img);
+    *img.get_image_mut() = rgb;
+    Ok(img)
+}
+
+/// Bilinear demosaicing of a `width` x `height` raw buffer into interleaved RGB.
+fn bilinear<T: Copy + Into<u32>>(
+    data: &[T],
+    width: usize,
+    height: usize,
+    cfa: [[usize; 2]; 2],
+    from: impl Fn(u32) -> T,
+) -> Vec<T> {
+    let mut out = Vec::with_capacity(width * height * 3);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 3];
+            let mut count = [0u32; 3];
+            for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                    let color = cfa[ny % 2][nx % 2];
+                    sum[color] += data[ny * width + nx].into();
+                    count[color] += 1;
+                }
+            }
+            let own = cfa[y % 2][x % 2];
+            for color in 0..3 {
+                out.push(if color == own {
+                    data[y * width + x]
+                } else {
+                    // a single row or column may not contain every color
+                    from(sum[color].checked_div(count[color]).unwrap_or(0))
+                });
+            }
+        }
+    }
+    out
+}
+
 /// Helper for letting a task sleep, abstracting over different async backends
 pub trait Sleep {
     /// Tells the current async task to be put to sleep for approximately `duration` amount of time.
@@ -214,7 +917,7 @@ mod test {
     #[cfg(not(feature = "loom"))]
     use std::{sync, thread};
     fn make_dummy() -> AnyGenCam {
-        let mut dummy = GenCamDriverDummy {};
+        let mut dummy = GenCamDriverDummy::default();
         let mut cam = dummy.connect_first_device().unwrap();
         let time = if cfg!(feature = "loom") {
             Duration::from_micros(0)
@@ -228,6 +931,39 @@ mod test {
         .unwrap();
         cam
     }
+    #[test]
+    fn demosaic() {
+        use crate::BayerPattern;
+        use refimage::{ColorSpace, DynamicImageOwned, ImageProps, ImageRef};
+
+        // a uniform scene of (200, 100, 50) seen through an RGGB filter
+        let (width, height) = (4, 4);
+        let mut raw: Vec<u16> = (0..width * height)
+            .map(|idx| match ((idx / width) % 2, idx % 2) {
+                (0, 0) => 200,
+                (1, 1) => 50,
+                _ => 100,
+            })
+            .collect();
+        fn make(raw: &mut [u16], width: usize, height: usize) -> refimage::GenericImageRef<'_> {
+            let img = ImageRef::new(raw, width, height, BayerPattern::Rggb.color_space()).unwrap();
+            refimage::GenericImageRef::new(std::time::SystemTime::UNIX_EPOCH, img.into())
+        }
+
+        let rgb = crate::demosaic(make(&mut raw, width, height), BayerPattern::Rggb).unwrap();
+        assert_eq!(rgb.color_space(), ColorSpace::Rgb);
+        assert_eq!(crate::channels_for(&rgb.color_space()), Some(3));
+        let DynamicImageOwned::U16(rgb) = rgb.get_image() else {
+            panic!("pixel type changed");
+        };
+        assert_eq!(rgb.as_slice().len(), width * height * 3);
+        assert!(rgb.as_slice().chunks(3).all(|px| px == [200, 100, 50]));
+
+        let same = crate::demosaic(make(&mut raw, width, height), BayerPattern::None).unwrap();
+        assert_eq!(same.color_space(), BayerPattern::Rggb.color_space());
+        assert_eq!(BayerPattern::from(&same.color_space()), BayerPattern::Rggb);
+    }
+
     #[cfg(not(feature = "loom"))]
     fn model(x: impl Fn() + Send + Sync + 'static) {
         x()
@@ -286,6 +1022,57 @@ mod test {
             );
         })
     }
+    #[cfg(all(feature = "tokio", not(feature = "loom")))]
+    #[tokio::test]
+    async fn dummy_capture_async() {
+        use crate::{CaptureAsync, TokioSleep};
+        let mut cam = make_dummy();
+        let img = cam.capture_async(TokioSleep).unwrap().await;
+        assert!(img.is_ok());
+        drop(img);
+        assert_eq!(cam.camera_state().unwrap(), GenCamState::ExposureFinished);
+    }
+    #[cfg(all(feature = "tokio", not(feature = "loom")))]
+    #[tokio::test]
+    async fn dummy_capture_async_cancel_on_drop() {
+        use crate::{CaptureAsync, TokioSleep};
+        let mut cam = make_dummy();
+        let fut = cam.capture_async(TokioSleep).unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), fut)
+                .await
+                .is_err()
+        );
+        assert!(!cam.is_capturing());
+    }
+    #[test]
+    fn flip_pixels() {
+        // 3x3 image with 2 channels per pixel
+        let image = |rows: [[u8; 3]; 3]| -> Vec<u8> {
+            rows.iter().flatten().flat_map(|&v| [v, v + 100]).collect()
+        };
+        let mut data = image([[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+        super::flip_pixels(&mut data, 3, 3, true, false);
+        assert_eq!(data, image([[2, 1, 0], [5, 4, 3], [8, 7, 6]]));
+        super::flip_pixels(&mut data, 3, 3, false, true);
+        assert_eq!(data, image([[8, 7, 6], [5, 4, 3], [2, 1, 0]]));
+        let mut data = vec![0u16, 1, 2, 3];
+        super::flip_pixels(&mut data, 2, 2, true, true);
+        assert_eq!(data, [3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn histogram_bins() {
+        let hist = super::histogram(&[0u16, 32767, 32768, 65535], 4, 2, |v| {
+            v as usize * 2 / (u16::MAX as usize + 1)
+        });
+        assert_eq!(hist, [[2, 2]]);
+        let bin = |v: f32| (v.clamp(0.0, 1.0) * 4.0) as usize;
+        let hist = super::histogram(&[-1.0f32, 0.3, 0.6, 1.0, 2.0, 0.0], 3, 4, bin);
+        assert_eq!(hist, [[1, 0, 1, 1], [1, 1, 0, 1]]);
+        assert!(super::histogram(&[1u8], 1, 0, |v| v as usize).is_empty());
+    }
+
     #[test]
     fn dummy_start_exposure_capture_guard_ok() {
         model(|| {
@@ -0,0 +1,322 @@
+/*!
+ * # Generic Camera Client
+ * This module contains a client that drives a camera exposed by a [`GenCamServer`]
+ * over TCP, using the wire format described in the [`server`](crate::server) module.
+ * The client can also drive a server in the same process, without sockets or
+ * serialization, e.g. for testing.
+ */
+use refimage::{DynamicImageOwned, DynamicImageRef, GenericImageOwned, GenericImageRef};
+use refimage::{ImageProps, ImageRef};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::server::{
+    FRAME_HEADER_LEN, GenCamServer, GenSrvCmd, GenSrvOutput, GenSrvValue, MAX_REPLY_LEN,
+};
+use crate::server::{decode_frame, encode_frame};
+use crate::{
+    AnyGenCamInfo, GenCam, GenCamCtrl, GenCamDescriptor, GenCamError, GenCamResult, GenCamRoi,
+    GenCamState, PollExposure, Property, PropertyValue,
+};
+
+/// A camera accessed remotely through a [`GenCamServer`].
+///
+/// Every [`GenCam`] call is sent to the server as a [`GenSrvCmd`], and errors
+/// returned by the server are passed through to the caller. The camera descriptor,
+/// vendor, name, property list and region of interest are cached on connection.
+///
+/// # Examples
+/// ```no_run
+/// use generic_camera::client::GenCamClient;
+/// use generic_camera::Capture;
+///
+/// let mut camera = GenCamClient::connect("127.0.0.1:9000", 1234).unwrap();
+/// let image = camera.capture().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct GenCamClient {
+    transport: Transport,
+    id: u32,
+    desc: GenCamDescriptor,
+    vendor: String,
+    name: String,
+    props: HashMap<GenCamCtrl, Property>,
+    roi: GenCamRoi,
+    image: Option<(SystemTime, DynamicImageOwned)>,
+}
+
+/// How a [`GenCamClient`] reaches the server.
+#[derive(Debug)]
+enum Transport {
+    /// Framed commands over a TCP connection.
+    Tcp(Mutex<TcpStream>),
+    /// Direct calls to a server owned by the client.
+    InProcess(GenCamServer),
+}
+
+impl GenCamClient {
+    /// Connect to the camera with the given ID on a server.
+    pub fn connect(addr: impl ToSocketAddrs, id: u32) -> GenCamResult<Self> {
+        let stream = TcpStream::connect(addr).map_err(io_err)?;
+        stream.set_nodelay(true).map_err(io_err)?;
+        Self::open(Transport::Tcp(Mutex::new(stream)), id)
+    }
+
+    /// Drive the camera with the given ID on a server owned by the client.
+    ///
+    /// Commands are passed to [`GenCamServer::execute_fn`] directly, without
+    /// serialization, so the client behaves like one connected over TCP, but
+    /// deterministically and without sockets.
+    ///
+    /// # Examples
+    /// ```
+    /// use generic_camera::client::GenCamClient;
+    /// use generic_camera::dummy::GenCamDriverDummy;
+    /// use generic_camera::server::GenCamServer;
+    /// use generic_camera::{Capture, GenCam, GenCamDriver};
+    ///
+    /// let mut server = GenCamServer::default();
+    /// let camera = GenCamDriverDummy::default().connect_first_device().unwrap();
+    /// let id = server.add_camera(camera).unwrap();
+    /// let mut camera = GenCamClient::in_process(server, id).unwrap();
+    /// camera.set_exposure(std::time::Duration::from_millis(1)).unwrap();
+    /// let image = camera.capture().unwrap();
+    /// ```
+    pub fn in_process(server: GenCamServer, id: u32) -> GenCamResult<Self> {
+        Self::open(Transport::InProcess(server), id)
+    }
+
+    /// Cache the camera information through `transport`.
+    fn open(transport: Transport, id: u32) -> GenCamResult<Self> {
+        let mut this = GenCamClient {
+            transport,
+            id,
+            desc: Default::default(),
+            vendor: Default::default(),
+            name: Default::default(),
+            props: Default::default(),
+            roi: Default::default(),
+            image: None,
+        };
+        this.desc = match this.call(GenSrvCmd::Info)? {
+            GenSrvValue::Info(desc) => desc,
+            _ => return Err(unexpected(GenSrvCmd::Info)),
+        };
+        this.vendor = this.call_str(GenSrvCmd::Vendor)?;
+        this.name = this.call_str(GenSrvCmd::CameraName)?;
+        this.props = match this.call(GenSrvCmd::ListProperties)? {
+            GenSrvValue::PropertyList(props) => props,
+            _ => return Err(unexpected(GenSrvCmd::ListProperties)),
+        };
+        this.roi = match this.call(GenSrvCmd::GetRoi)? {
+            GenSrvValue::Roi(roi) => roi,
+            _ => return Err(unexpected(GenSrvCmd::GetRoi)),
+        };
+        Ok(this)
+    }
+
+    /// Get the ID of the camera on the server.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Capture an image using [`GenSrvCmd::Capture`], blocking until the server replies.
+    ///
+    /// Unlike [`Capture::capture`](crate::Capture::capture), this returns the image
+    /// exactly as it was sent by the server, including all metadata.
+    pub fn capture_owned(&mut self) -> GenCamResult<GenericImageOwned> {
+        match self.call(GenSrvCmd::Capture)? {
+            GenSrvValue::Image(img) => Ok(img),
+            _ => Err(unexpected(GenSrvCmd::Capture)),
+        }
+    }
+
+    /// Send a command to the server and wait for the reply.
+    ///
+    /// Replies longer than [`MAX_REPLY_LEN`] are rejected with
+    /// [`GenCamError::InvalidSize`] without reading them, which leaves the connection
+    /// unusable.
+    fn call(&self, cmd: GenSrvCmd) -> GenCamResult<GenSrvValue> {
+        let stream = match &self.transport {
+            Transport::Tcp(stream) => stream,
+            Transport::InProcess(server) => return server.execute_fn(self.id, cmd),
+        };
+        let mut stream = stream.lock().map_err(|_| GenCamError::AccessViolation)?;
+        stream
+            .write_all(&encode_frame(&(self.id, cmd)))
+            .map_err(io_err)?;
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        stream.read_exact(&mut header).map_err(io_err)?;
+        let len = u32::from_le_bytes(header) as usize;
+        if len > MAX_REPLY_LEN {
+            return Err(GenCamError::InvalidSize(len));
+        }
+        let mut frame = vec![0u8; FRAME_HEADER_LEN + len];
+        frame[..FRAME_HEADER_LEN].copy_from_slice(&header);
+        stream
+            .read_exact(&mut frame[FRAME_HEADER_LEN..])
+            .map_err(io_err)?;
+        decode_frame::<GenSrvOutput>(&frame)?
+    }
+
+    /// Send a command that returns a property value.
+    fn call_value(&self, cmd: GenSrvCmd) -> GenCamResult<(PropertyValue, Option<bool>)> {
+        match self.call(cmd.clone())? {
+            GenSrvValue::Property { value, auto } => Ok((value, auto)),
+            _ => Err(unexpected(cmd)),
+        }
+    }
+
+    /// Send a command that returns a string.
+    fn call_str(&self, cmd: GenSrvCmd) -> GenCamResult<String> {
+        match self.call_value(cmd.clone())?.0 {
+            PropertyValue::EnumStr(s) => Ok(s),
+            _ => Err(unexpected(cmd)),
+        }
+    }
+
+    /// Send a command that returns a boolean.
+    fn call_bool(&self, cmd: GenSrvCmd) -> GenCamResult<bool> {
+        match self.call_value(cmd.clone())?.0 {
+            PropertyValue::Bool(b) => Ok(b),
+            _ => Err(unexpected(cmd)),
+        }
+    }
+
+    /// Send a command that returns nothing.
+    fn call_unit(&self, cmd: GenSrvCmd) -> GenCamResult<()> {
+        match self.call(cmd.clone())? {
+            GenSrvValue::Unit => Ok(()),
+            _ => Err(unexpected(cmd)),
+        }
+    }
+
+    /// Borrow the last received image.
+    fn image_ref(&mut self) -> GenCamResult<GenericImageRef<'_>> {
+        let Some((tstamp, image)) = self.image.as_mut() else {
+            return Err(GenCamError::InvalidSequence);
+        };
+        let image: DynamicImageRef = match image {
+            DynamicImageOwned::U8(img) => {
+                let (width, height, cspace) = (img.width(), img.height(), img.color_space());
+                ImageRef::new(img.as_mut_slice(), width, height, cspace)
+                    .map_err(|e| GenCamError::InvalidImageType(e.to_string()))?
+                    .into()
+            }
+            DynamicImageOwned::U16(img) => {
+                let (width, height, cspace) = (img.width(), img.height(), img.color_space());
+                ImageRef::new(img.as_mut_slice(), width, height, cspace)
+                    .map_err(|e| GenCamError::InvalidImageType(e.to_string()))?
+                    .into()
+            }
+            DynamicImageOwned::F32(img) => {
+                let (width, height, cspace) = (img.width(), img.height(), img.color_space());
+                ImageRef::new(img.as_mut_slice(), width, height, cspace)
+                    .map_err(|e| GenCamError::InvalidImageType(e.to_string()))?
+                    .into()
+            }
+        };
+        Ok(GenericImageRef::new(*tstamp, image))
+    }
+}
+
+impl GenCam for GenCamClient {
+    fn info_handle(&self) -> Option<AnyGenCamInfo> {
+        // info handles can not be sent over the wire
+        None
+    }
+
+    fn info(&self) -> GenCamResult<&GenCamDescriptor> {
+        Ok(&self.desc)
+    }
+
+    fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    fn camera_ready(&self) -> bool {
+        self.call_bool(GenSrvCmd::CameraReady).unwrap_or(false)
+    }
+
+    fn camera_name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_properties(&self) -> &HashMap<GenCamCtrl, Property> {
+        &self.props
+    }
+
+    fn get_property(&self, name: GenCamCtrl) -> GenCamResult<(PropertyValue, bool)> {
+        let (value, auto) = self.call_value(GenSrvCmd::GetProperty(name))?;
+        Ok((value, auto.unwrap_or(false)))
+    }
+
+    fn set_property(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()> {
+        self.call_unit(GenSrvCmd::SetProperty(name, value.clone(), false))
+    }
+
+    fn set_property_auto(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()> {
+        self.call_unit(GenSrvCmd::SetProperty(name, value.clone(), true))
+    }
+
+    fn cancel_capture(&self) -> GenCamResult<()> {
+        self.call_unit(GenSrvCmd::CancelCapture)
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.call_bool(GenSrvCmd::IsCapturing).unwrap_or(false)
+    }
+
+    fn start_exposure(&mut self) -> GenCamResult<()> {
+        self.call_unit(GenSrvCmd::StartExposure)
+    }
+
+    /// Poll the exposure on the server.
+    ///
+    /// The image is copied out of the reply; metadata other than the timestamp
+    /// is not preserved. Use [`GenCamClient::capture_owned`] to keep the metadata.
+    fn poll_exposure(&mut self) -> PollExposure<'_> {
+        match self.call(GenSrvCmd::PollExposure) {
+            Ok(GenSrvValue::Image(img)) => {
+                self.image = Some((img.get_timestamp(), img.get_image().clone()));
+                PollExposure::Ready(self.image_ref())
+            }
+            Ok(GenSrvValue::Wait(Some(dur))) => PollExposure::Wait(dur),
+            Ok(GenSrvValue::Wait(None)) => PollExposure::Soon,
+            Ok(_) => PollExposure::Ready(Err(unexpected(GenSrvCmd::PollExposure))),
+            Err(e) => PollExposure::Ready(Err(e)),
+        }
+    }
+
+    fn camera_state(&self) -> GenCamResult<GenCamState> {
+        match self.call(GenSrvCmd::CameraState)? {
+            GenSrvValue::State(state) => Ok(state),
+            _ => Err(unexpected(GenSrvCmd::CameraState)),
+        }
+    }
+
+    fn set_roi(&mut self, roi: &GenCamRoi) -> GenCamResult<&GenCamRoi> {
+        match self.call(GenSrvCmd::SetRoi(*roi))? {
+            GenSrvValue::Roi(roi) => {
+                self.roi = roi;
+                Ok(&self.roi)
+            }
+            _ => Err(unexpected(GenSrvCmd::SetRoi(*roi))),
+        }
+    }
+
+    fn get_roi(&self) -> &GenCamRoi {
+        &self.roi
+    }
+}
+
+fn io_err(e: std::io::Error) -> GenCamError {
+    GenCamError::GeneralError(e.to_string())
+}
+
+fn unexpected(cmd: GenSrvCmd) -> GenCamError {
+    GenCamError::InvalidFormat(format!("Unexpected reply to {cmd:?}"))
+}
@@ -4,6 +4,7 @@
  */
 #[allow(unused_imports)]
 use crate::PropertyType;
+use crate::property::PropertyError;
 use documented::{Documented, DocumentedVariants};
 use senti::cstring::BoundedCString;
 use serde::{Deserialize, Serialize};
@@ -64,6 +65,22 @@ impl CustomName {
         Some(Self(inner))
     }
 
+    /// Create a new custom name without any implicit truncation.
+    ///
+    /// # Errors
+    /// - [`PropertyError::NameTooLong`] if the name is longer than 16 bytes.
+    /// - [`PropertyError::ValueNotSupported`] if the name is empty, contains a null byte,
+    ///   or contains characters other than ASCII alphanumerics and `-_# `.
+    pub fn try_new(name: &str) -> Result<Self, PropertyError> {
+        if name.len() > 16 {
+            return Err(PropertyError::NameTooLong(name.len()));
+        }
+        if name.as_bytes().contains(&b'\0') {
+            return Err(PropertyError::ValueNotSupported);
+        }
+        Self::new(name).ok_or(PropertyError::ValueNotSupported)
+    }
+
     /// Get the custom name as a string.
     pub fn as_str(&self) -> &str {
         // SAFETY: The constructor ensures that we are valid UTF-8
@@ -79,6 +96,12 @@ impl Serialize for CustomName {
         self.as_str().serialize(serializer)
     }
 }
+
+impl std::fmt::Display for CustomName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 use serde::de::Error;
 impl<'de> Deserialize<'de> for CustomName {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -199,6 +222,8 @@ pub enum SensorCtrl {
     PixelFormat,
     /// Apply a test pattern to the image ([`PropertyType::EnumStr`])
     TestPattern,
+    /// Select the sensor read-out (horizontal shift) speed, trading read noise for frame rate ([`PropertyType::EnumStr`] or [`PropertyType::EnumUnsigned`])
+    ReadoutSpeed,
     /// A custom command
     Custom(CustomName),
 }
@@ -227,6 +252,13 @@ pub enum TriggerCtrl {
     Custom(CustomName),
 }
 
+impl TriggerCtrl {
+    /// Fire a software trigger by writing [`PropertyValue::Command`](crate::PropertyValue::Command),
+    /// named after the GenICam `TriggerSoftware` command. See [`GenCam::trigger_now`](crate::GenCam::trigger_now).
+    pub const SOFTWARE: TriggerCtrl =
+        TriggerCtrl::Custom(CustomName::new("TriggerSoftware").unwrap());
+}
+
 /// Describes exposure control options.
 #[derive(
     Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Hash, Eq, Documented, DocumentedVariants,
@@ -235,7 +267,7 @@ pub enum TriggerCtrl {
 pub enum ExposureCtrl {
     /// Select exposure mode ([`PropertyType::EnumStr`])
     Mode,
-    /// Select exposure time ([`PropertyType::Duration`])
+    /// Select exposure time ([`PropertyType::Duration`], or [`PropertyType::Float`] in the unit given by [`GenCam::exposure_unit`](crate::GenCam::exposure_unit))
     ExposureTime,
     /// Select exposure auto mode ([`PropertyType::EnumStr`] or [`PropertyType::Bool`])
     Auto,
@@ -367,6 +399,191 @@ impl_from_ctrl!(FrameTimeCtrl, FrameTime);
 impl_from_ctrl!(AnalogCtrl, Analog);
 impl_from_ctrl!(DigitalIoCtrl, DigitalIo);
 
+macro_rules! impl_display_ctrl {
+    ($ctrl:ident) => {
+        impl std::fmt::Display for $ctrl {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $ctrl::Custom(name) => name.fmt(f),
+                    _ => write!(f, "{self:?}"),
+                }
+            }
+        }
+    };
+}
+
+impl_display_ctrl!(DeviceCtrl);
+impl_display_ctrl!(SensorCtrl);
+impl_display_ctrl!(TriggerCtrl);
+impl_display_ctrl!(ExposureCtrl);
+impl_display_ctrl!(FrameTimeCtrl);
+impl_display_ctrl!(AnalogCtrl);
+impl_display_ctrl!(DigitalIoCtrl);
+
+impl std::fmt::Display for GenCamCtrl {
+    /// Formats the control as `Zone::Control`, e.g. `Exposure::ExposureTime`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenCamCtrl::Device(ctrl) => write!(f, "Device::{ctrl}"),
+            GenCamCtrl::Sensor(ctrl) => write!(f, "Sensor::{ctrl}"),
+            GenCamCtrl::Trigger(ctrl) => write!(f, "Trigger::{ctrl}"),
+            GenCamCtrl::Exposure(ctrl) => write!(f, "Exposure::{ctrl}"),
+            GenCamCtrl::FrameTime(ctrl) => write!(f, "FrameTime::{ctrl}"),
+            GenCamCtrl::Analog(ctrl) => write!(f, "Analog::{ctrl}"),
+            GenCamCtrl::DigitalIo(ctrl) => write!(f, "DigitalIo::{ctrl}"),
+        }
+    }
+}
+
+macro_rules! impl_all_ctrl {
+    ($ctrl:ident, [$($variant:ident),* $(,)?]) => {
+        impl $ctrl {
+            /// All known variants of this control, excluding `Custom`.
+            pub const fn all() -> &'static [$ctrl] {
+                &[$($ctrl::$variant),*]
+            }
+        }
+
+        // a variant missing from the list is a compile error here
+        const _: fn(&$ctrl) = |ctrl| match ctrl {
+            $($ctrl::$variant)|* | $ctrl::Custom(_) => {}
+        };
+    };
+}
+
+impl_all_ctrl!(
+    DeviceCtrl,
+    [
+        ScanType,
+        VendorName,
+        ModelName,
+        FamilyName,
+        MfgInfo,
+        Version,
+        FwVersion,
+        SerialNumber,
+        Id,
+        UserId,
+        TlType,
+        TemperatureSelector,
+        Temperature,
+        Reset,
+        CoolerTemp,
+        CoolerPower,
+        CoolerEnable,
+        HighSpeedMode,
+        FanToggle,
+    ]
+);
+impl_all_ctrl!(
+    SensorCtrl,
+    [
+        PixelWidth,
+        PixelHeight,
+        Name,
+        ShutterMode,
+        WidthMax,
+        HeightMax,
+        BinningSelector,
+        BinningBoth,
+        BinningHorzlMode,
+        BinningVertMode,
+        BinningHorz,
+        BinningVert,
+        DecimationHorzMode,
+        DecimationHorz,
+        DecimationVertMode,
+        DecimationVert,
+        ReverseX,
+        ReverseY,
+        PixelFormat,
+        TestPattern,
+        ReadoutSpeed,
+    ]
+);
+impl_all_ctrl!(
+    TriggerCtrl,
+    [Sel, Mod, Src, Overlap, Delay, Divider, Multiplier]
+);
+impl_all_ctrl!(
+    ExposureCtrl,
+    [
+        Mode,
+        ExposureTime,
+        Auto,
+        AutoMaxExposure,
+        AutoTargetBrightness,
+        AutoMaxGain,
+    ]
+);
+impl_all_ctrl!(FrameTimeCtrl, [Mode, FrameTime, Auto]);
+impl_all_ctrl!(
+    AnalogCtrl,
+    [
+        GainSelector,
+        Gain,
+        GainAuto,
+        GainAutoBalance,
+        BlackLevelSel,
+        BlackLevel,
+        BlackLevelAuto,
+        BlackLevelAutoBalance,
+        WhiteClipSel,
+        WhiteClip,
+        BalanceRatioSel,
+        BalanceRatio,
+        BalanceWhiteAuto,
+        Gamma,
+    ]
+);
+impl_all_ctrl!(
+    DigitalIoCtrl,
+    [
+        LineSel, LineMod, LineInvert, LineStat, LineSrc, UserOutSel, UserOutVal,
+    ]
+);
+
+impl GenCamCtrl {
+    /// All known controls of every zone, excluding `Custom` controls.
+    ///
+    /// This is useful for generating user interfaces that list every control a camera could expose.
+    pub fn all_known() -> Vec<GenCamCtrl> {
+        let mut all = Vec::new();
+        all.extend(DeviceCtrl::all().iter().copied().map(GenCamCtrl::from));
+        all.extend(SensorCtrl::all().iter().copied().map(GenCamCtrl::from));
+        all.extend(TriggerCtrl::all().iter().copied().map(GenCamCtrl::from));
+        all.extend(ExposureCtrl::all().iter().copied().map(GenCamCtrl::from));
+        all.extend(FrameTimeCtrl::all().iter().copied().map(GenCamCtrl::from));
+        all.extend(AnalogCtrl::all().iter().copied().map(GenCamCtrl::from));
+        all.extend(DigitalIoCtrl::all().iter().copied().map(GenCamCtrl::from));
+        all
+    }
+
+    /// Compare controls by zone, then by variant, in the order of [`GenCamCtrl::all_known`].
+    /// `Custom` controls come last in their zone, ordered by name.
+    pub(crate) fn stable_cmp(&self, other: &GenCamCtrl) -> std::cmp::Ordering {
+        self.sort_key()
+            .cmp(&other.sort_key())
+            .then_with(|| self.to_string().cmp(&other.to_string()))
+    }
+
+    /// The zone and variant index of the control.
+    fn sort_key(&self) -> (u8, usize) {
+        fn index<T: PartialEq>(all: &[T], ctrl: &T) -> usize {
+            all.iter().position(|c| c == ctrl).unwrap_or(all.len())
+        }
+        match self {
+            GenCamCtrl::Device(ctrl) => (0, index(DeviceCtrl::all(), ctrl)),
+            GenCamCtrl::Sensor(ctrl) => (1, index(SensorCtrl::all(), ctrl)),
+            GenCamCtrl::Trigger(ctrl) => (2, index(TriggerCtrl::all(), ctrl)),
+            GenCamCtrl::Exposure(ctrl) => (3, index(ExposureCtrl::all(), ctrl)),
+            GenCamCtrl::FrameTime(ctrl) => (4, index(FrameTimeCtrl::all(), ctrl)),
+            GenCamCtrl::Analog(ctrl) => (5, index(AnalogCtrl::all(), ctrl)),
+            GenCamCtrl::DigitalIo(ctrl) => (6, index(DigitalIoCtrl::all(), ctrl)),
+        }
+    }
+}
+
 /// Trait for controls that have a tooltip.
 pub trait ToolTip {
     /// The tooltip for this control.
@@ -391,3 +608,96 @@ impl_tooltip!(FrameTimeCtrl);
 impl_tooltip!(AnalogCtrl);
 impl_tooltip!(DigitalIoCtrl);
 impl_tooltip!(GenCamCtrl);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn custom_name_multibyte() {
+        // the constructor never truncates, so a split codepoint can not reach `as_str`
+        assert_eq!(CustomName::new("123456789012345\u{1F600}"), None);
+        assert_eq!(CustomName::new("12345678901\u{1F600}"), None);
+        let name = CustomName::new("123456789012345").unwrap();
+        assert_eq!(name.as_str(), "123456789012345");
+    }
+
+    #[test]
+    fn custom_name_try_new() {
+        assert_eq!(
+            CustomName::try_new("Product ID").unwrap().as_str(),
+            "Product ID"
+        );
+        assert_eq!(
+            CustomName::try_new("ABCDEFGHIJKLMNOPQRST"),
+            Err(PropertyError::NameTooLong(20))
+        );
+        // a multibyte character straddling the 16 byte boundary
+        assert_eq!(
+            CustomName::try_new("123456789012345\u{1F600}"),
+            Err(PropertyError::NameTooLong(19))
+        );
+        // a multibyte character within the length limit
+        assert_eq!(
+            CustomName::try_new("12345678901\u{1F600}"),
+            Err(PropertyError::ValueNotSupported)
+        );
+        assert_eq!(
+            CustomName::try_new("abc\0def"),
+            Err(PropertyError::ValueNotSupported)
+        );
+        assert_eq!(
+            CustomName::try_new(""),
+            Err(PropertyError::ValueNotSupported)
+        );
+    }
+
+    #[test]
+    fn all_known_ctrls() {
+        // `impl_all_ctrl` checks that every variant is listed, so only check that none
+        // is listed twice
+        let all = GenCamCtrl::all_known();
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), all.len());
+    }
+
+    #[test]
+    fn readout_speed() {
+        let ctrl = GenCamCtrl::Sensor(SensorCtrl::ReadoutSpeed);
+        assert_eq!(SensorCtrl::ReadoutSpeed.to_string(), "ReadoutSpeed");
+        assert_eq!(ctrl.to_string(), "Sensor::ReadoutSpeed");
+        assert!(
+            SensorCtrl::ReadoutSpeed
+                .tooltip()
+                .starts_with("Select the sensor read-out")
+        );
+        let json = serde_json::to_string(&ctrl).unwrap();
+        assert_eq!(serde_json::from_str::<GenCamCtrl>(&json).unwrap(), ctrl);
+
+        // listed last among the known sensor controls, before custom ones
+        let all = GenCamCtrl::all_known();
+        assert!(all.contains(&ctrl));
+        let test_pattern = GenCamCtrl::Sensor(SensorCtrl::TestPattern);
+        assert!(test_pattern.stable_cmp(&ctrl).is_lt());
+        let custom = GenCamCtrl::Sensor(SensorCtrl::Custom(CustomName::new("Aaa").unwrap()));
+        assert!(ctrl.stable_cmp(&custom).is_lt());
+        assert!(
+            ctrl.stable_cmp(&GenCamCtrl::Trigger(TriggerCtrl::Sel))
+                .is_lt()
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            GenCamCtrl::Exposure(ExposureCtrl::ExposureTime).to_string(),
+            "Exposure::ExposureTime"
+        );
+        assert_eq!(AnalogCtrl::Gain.to_string(), "Gain");
+        let name = CustomName::new("UUID").unwrap();
+        assert_eq!(
+            GenCamCtrl::Device(DeviceCtrl::Custom(name)).to_string(),
+            "Device::UUID"
+        );
+    }
+}
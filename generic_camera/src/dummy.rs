@@ -8,7 +8,7 @@ use generic_camera::dummy::{GenCamDriverDummy, GenCamDummy};
 use generic_camera::{GenCam, GenCamDriver, Capture};
 use generic_camera::{GenCamCtrl, controls::ExposureCtrl};
 use std::time::Duration;
-let mut driver = GenCamDriverDummy {};
+let mut driver = GenCamDriverDummy::default();
 let mut camera = driver.connect_first_device().expect("Failed to connect to camera");
 
 let img = camera.capture().expect("Failed to capture image");
@@ -26,78 +26,68 @@ use cell::UnsafeCell;
 use std::{
     collections::HashMap,
     fmt::Debug,
+    sync::mpsc::{Receiver, Sender, channel},
     time::{Duration, Instant, SystemTime},
 };
-use sync::atomic::{AtomicU8, Ordering, fence};
+use sync::atomic::{AtomicBool, AtomicU8, Ordering, fence};
 use sync::{Arc, Mutex};
 
-use rand::{Rng, thread_rng};
+use rand::{Rng, SeedableRng, rngs::StdRng, thread_rng};
 
-use refimage::{DynamicImageRef, GenericImageRef, ImageRef};
+use refimage::{DynamicImageRef, GenericImageOwned, GenericImageRef, ImageRef};
 
 use crate::{
-    GenCam, GenCamCtrl, GenCamDescriptor, GenCamDriver, GenCamError, GenCamResult, GenCamRoi,
-    GenCamState, PollExposure, Property, PropertyError, PropertyValue, controls::ExposureCtrl,
+    Capabilities, Capture, DeviceChanges, FrameMeta, FrameStream, GenCam, GenCamCtrl,
+    GenCamDescriptor, GenCamDriver, GenCamError, GenCamInfo, GenCamPixelBpp, GenCamResult,
+    GenCamRoi, GenCamState, PollExposure, Property, PropertyError, PropertySnapshot, PropertyValue,
+    controls::{AnalogCtrl, ExposureCtrl, SensorCtrl},
+    metadata_keys,
     property::PropertyLims,
+    validate_roi,
 };
 
 #[derive(Debug)]
 /// A dummy driver for testing purposes.
-pub struct GenCamDriverDummy {}
+pub struct GenCamDriverDummy {
+    plugged: bool,
+    listed: Vec<GenCamDescriptor>,
+}
+
+impl Default for GenCamDriverDummy {
+    fn default() -> Self {
+        Self {
+            plugged: true,
+            listed: Vec::new(),
+        }
+    }
+}
+
+impl GenCamDriverDummy {
+    /// Simulate plugging in (`true`, the default) or unplugging (`false`) the dummy camera.
+    ///
+    /// This changes which devices are listed, and is reported by
+    /// [`GenCamDriver::poll_changes`]. Cameras that are already connected are not affected.
+    pub fn set_plugged(&mut self, plugged: bool) {
+        self.plugged = plugged;
+    }
+}
 
 impl GenCamDriver for GenCamDriverDummy {
     fn available_devices(&self) -> usize {
-        1
+        self.plugged as usize
     }
 
     fn list_devices(&mut self) -> GenCamResult<Vec<GenCamDescriptor>> {
-        let mut desc = GenCamDescriptor {
-            vendor: "Dummy".to_string(),
-            name: "Dummy Camera".to_string(),
-            id: 0xdeadbeef,
-            ..Default::default()
+        self.listed = if self.plugged {
+            vec![dummy_descriptor()]
+        } else {
+            vec![]
         };
-        desc.info.insert("Interface".into(), "Aether".into());
-        Ok(vec![desc])
+        Ok(self.listed.clone())
     }
 
     fn connect_device(&mut self, descriptor: &GenCamDescriptor) -> GenCamResult<crate::AnyGenCam> {
-        let mut caps = HashMap::new();
-        caps.insert(
-            GenCamCtrl::Exposure(ExposureCtrl::ExposureTime),
-            Property::new(
-                PropertyLims::Duration {
-                    min: Duration::from_millis(1),
-                    max: Duration::from_secs(60),
-                    step: Duration::from_millis(1),
-                    default: Duration::from_secs(1),
-                },
-                false,
-                false,
-            ),
-        );
-        let mut vals = HashMap::new();
-        vals.insert(
-            GenCamCtrl::Exposure(ExposureCtrl::ExposureTime),
-            (PropertyValue::Duration(Duration::from_secs(1)), false),
-        );
-        Ok(Box::new(GenCamDummy {
-            desc: descriptor.clone(),
-            name: descriptor.name.clone(),
-            vendor: descriptor.vendor.clone(),
-            caps,
-            vals: Mutex::new(vals),
-            // capturing: Arc::new(AtomicBool::new(false)),
-            roi: GenCamRoi {
-                x_min: 0,
-                y_min: 0,
-                width: 1920,
-                height: 1080,
-            },
-            data: vec![0; 1920 * 1080 * 3],
-            // imgready: Arc::new(AtomicBool::new(false)),
-            capture_state: Arc::new(CaptureState::new()), // start: AtomicOptionInstant::none(),
-        }))
+        Ok(Box::new(GenCamDummy::new(descriptor)))
     }
 
     fn connect_first_device(&mut self) -> GenCamResult<crate::AnyGenCam> {
@@ -107,12 +97,70 @@ impl GenCamDriver for GenCamDriverDummy {
             .ok_or(GenCamError::NoCamerasAvailable)?;
         self.connect_device(&desc)
     }
+
+    fn poll_changes(&mut self) -> GenCamResult<DeviceChanges> {
+        let old = std::mem::take(&mut self.listed);
+        Ok(DeviceChanges::between(&old, &self.list_devices()?))
+    }
+}
+
+/// The descriptor of the (only) dummy camera.
+fn dummy_descriptor() -> GenCamDescriptor {
+    let mut desc = GenCamDescriptor {
+        vendor: "Dummy".to_string(),
+        name: "Dummy Camera".to_string(),
+        id: 0xdeadbeef,
+        ..Default::default()
+    };
+    desc.info.insert("Interface".into(), "Aether".into());
+    desc
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+/// The pattern of the images generated by the dummy camera.
+pub enum DummyPattern {
+    /// Random noise. This is the default.
+    ///
+    /// The noise is reproducible for a given seed, see [`GenCamDummy::with_seed`].
+    #[default]
+    Noise,
+    /// A horizontal gradient, from 0 on the left edge to 255 on the right edge.
+    Gradient,
+    /// Every pixel set to the same value.
+    SolidValue(u8),
+    /// A checkerboard of black and white squares, starting with black at the origin.
+    Checkerboard {
+        /// The size of the squares, in pixels.
+        size: u16,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+/// How often an error set with [`GenCamDummy::set_failure`] is returned.
+pub enum FailureMode {
+    /// The error is returned by the next capture operation only. This is the default.
+    #[default]
+    Once,
+    /// The error is returned by every capture operation, until it is cleared.
+    Always,
 }
 
-/// The capture state for the dummy camera
+/// The capture state for the dummy camera, shared by the camera, its info handle and
+/// the exposure thread.
+///
+/// Once [`CaptureState::cancel_capture`] returns `Ok`, every thread observes the
+/// camera as idle, and the exposure can no longer be marked ready, since
+/// [`CaptureState::mark_ready`] only succeeds on a capturing state. The exposure
+/// thread waiting in [`CaptureState::wait_timeout`] is woken up by the cancellation,
+/// since the notification is sent under the same mutex the thread checks the state with.
 struct CaptureState {
     state: AtomicU8,
     start_time: UnsafeCell<Instant>,
+    // This is only used to put the exposure thread to sleep, and is kept out of the loom model.
+    wake: (std::sync::Mutex<()>, std::sync::Condvar),
+    // The start and length of a simulated download. This is only used while the state is
+    // DOWNLOADING, which the loom model never enters, so it is kept out of the model.
+    download: std::sync::Mutex<Option<(Instant, Duration)>>,
 }
 unsafe impl Send for CaptureState {}
 unsafe impl Sync for CaptureState {}
@@ -152,11 +200,18 @@ impl CaptureState {
     const CAPTURING: u8 = 2;
     /// We have finished a capture
     const READY: u8 = 3;
+    /// The capture was cancelled, and the cancellation has not been reported yet.
+    /// This is reported as idle otherwise.
+    const CANCELLED: u8 = 4;
+    /// A finished capture is being downloaded.
+    const DOWNLOADING: u8 = 5;
 
     pub fn new() -> Self {
         Self {
             state: AtomicU8::new(Self::IDLE),
             start_time: UnsafeCell::new(Instant::now()),
+            wake: Default::default(),
+            download: Default::default(),
         }
     }
     fn is_state_capturing(x: u8) -> bool {
@@ -165,7 +220,11 @@ impl CaptureState {
     pub fn is_capturing(&self, order: Ordering) -> bool {
         Self::is_state_capturing(self.state.load(order))
     }
-    pub fn start_capture(&self) -> GenCamResult<Instant> {
+    /// Start a capture that begins exposing after `latency`, returning the start time.
+    ///
+    /// Until the start time, the capture is reported as exposing for an unknown time,
+    /// like while the start time is being updated.
+    pub fn start_capture(&self, latency: Duration) -> GenCamResult<Instant> {
         let old = preserve_or_store(
             &self.state,
             Self::CAPTURING,
@@ -180,7 +239,7 @@ impl CaptureState {
         // If the state change did not result in an error, we want the change to
         // happen before all of these stores,
         fence(Ordering::AcqRel);
-        let now = Instant::now();
+        let now = Instant::now() + latency;
         // SAFETY: We have exclusive access over self.start_time. Access to self.start_time
         // is guarded by self.state being WAITING_FOR_TIME
         #[cfg(all(feature = "loom", not(doctest)))]
@@ -198,32 +257,49 @@ impl CaptureState {
         Ok(now)
     }
 
-    pub fn get_state(&self) -> GenCamState {
-        if cfg!(feature = "loom") {
-            thread::yield_now();
-        }
-        match self.state.compare_exchange(
+    /// Read the start time of the capture, or get the current state if it is not
+    /// capturing, or someone else holds the start time.
+    fn start_time(&self) -> Result<Instant, u8> {
+        self.state.compare_exchange(
             Self::CAPTURING,
             Self::WAITING_FOR_TIME,
             Ordering::AcqRel,
             Ordering::Relaxed,
-        ) {
-            Ok(_) => {
-                // SAFETY: we were capturing, now we obtained the lock over the start time.
-                #[cfg(all(feature = "loom", not(doctest)))]
-                let start = { unsafe { *self.start_time.get().deref() } };
-                #[cfg(not(all(feature = "loom", not(doctest))))]
-                let start = unsafe { self.start_time.get().read() };
-
-                self.state.store(Self::CAPTURING, Ordering::Release);
-                GenCamState::Exposing(Some(start.elapsed()))
-            }
+        )?;
+        // SAFETY: we were capturing, now we obtained the lock over the start time.
+        #[cfg(all(feature = "loom", not(doctest)))]
+        let start = { unsafe { *self.start_time.get().deref() } };
+        #[cfg(not(all(feature = "loom", not(doctest))))]
+        let start = unsafe { self.start_time.get().read() };
+
+        self.state.store(Self::CAPTURING, Ordering::Release);
+        Ok(start)
+    }
+    /// The time left until the exposure starts, if the capture is waiting to start.
+    pub fn start_delay(&self) -> Option<Duration> {
+        let start = self.start_time().ok()?;
+        start.checked_duration_since(Instant::now())
+    }
+
+    pub fn get_state(&self) -> GenCamState {
+        if cfg!(feature = "loom") {
+            thread::yield_now();
+        }
+        match self.start_time() {
+            // the exposure has not started yet
+            Ok(start) if start > Instant::now() => GenCamState::Exposing(None),
+            Ok(start) => GenCamState::Exposing(Some(start.elapsed())),
             // Someone else is updating or reading the start time,
             // just spuriously indicate that the exposing time is unknown.
             // We don't want to spin loop.
             Err(Self::WAITING_FOR_TIME) => GenCamState::Exposing(None),
-            Err(Self::IDLE) => GenCamState::Idle,
+            Err(Self::IDLE | Self::CANCELLED) => GenCamState::Idle,
             Err(Self::READY) => GenCamState::ExposureFinished,
+            Err(Self::DOWNLOADING) => {
+                let (start, delay) = self.download_progress();
+                let percent = (start.elapsed().as_secs_f64() / delay.as_secs_f64()).min(1.0);
+                GenCamState::Downloading(Some((percent * 100.0) as u32))
+            }
             _ => GenCamState::Unknown,
         }
     }
@@ -279,11 +355,132 @@ impl CaptureState {
         }
     }
     pub fn cancel_capture(&self) -> GenCamResult<()> {
-        self.wait_until_capture_and_then_update_state(Self::IDLE)
+        self.wait_until_capture_and_then_update_state(Self::CANCELLED)?;
+        let (lock, cvar) = &self.wake;
+        let _guard = lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        cvar.notify_all();
+        Ok(())
     }
     pub fn mark_ready(&self) -> GenCamResult<()> {
         self.wait_until_capture_and_then_update_state(Self::READY)
     }
+    /// Check if the last capture was cancelled, resetting the state to idle if so.
+    pub fn take_cancelled(&self) -> bool {
+        self.state
+            .compare_exchange(
+                Self::CANCELLED,
+                Self::IDLE,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+    /// Start downloading a finished capture, taking `delay`.
+    ///
+    /// Returns `false` if the capture is not ready to be downloaded.
+    pub fn start_download(&self, delay: Duration) -> bool {
+        *self.download_lock() = Some((Instant::now(), delay));
+        self.state
+            .compare_exchange(
+                Self::READY,
+                Self::DOWNLOADING,
+                Ordering::Release,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+    /// The time left until the download finishes.
+    pub fn download_remaining(&self) -> Duration {
+        let (start, delay) = self.download_progress();
+        delay.saturating_sub(start.elapsed())
+    }
+    /// Finish the download, returning to the ready state.
+    pub fn finish_download(&self) {
+        _ = self.state.compare_exchange(
+            Self::DOWNLOADING,
+            Self::READY,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+    fn download_lock(&self) -> std::sync::MutexGuard<'_, Option<(Instant, Duration)>> {
+        self.download
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+    fn download_progress(&self) -> (Instant, Duration) {
+        self.download_lock()
+            .unwrap_or((Instant::now(), Duration::ZERO))
+    }
+    /// Cancel any capture, and return to the idle state.
+    pub fn reset(&self) {
+        _ = self.cancel_capture();
+        self.state.store(Self::IDLE, Ordering::Release);
+    }
+    /// Sleep for up to `timeout`, returning early if the capture is cancelled.
+    pub fn wait_timeout(&self, timeout: Duration) {
+        let (lock, cvar) = &self.wake;
+        let guard = lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // checked under the lock, so that a cancellation can not slip in before we wait
+        if self.is_capturing(Ordering::Relaxed) {
+            _ = cvar.wait_timeout(guard, timeout);
+        }
+    }
+}
+
+/// Read a property value of a dummy camera.
+fn get_value(
+    vals: &Mutex<HashMap<GenCamCtrl, (PropertyValue, bool)>>,
+    name: GenCamCtrl,
+) -> GenCamResult<(PropertyValue, bool)> {
+    // deadlock me not
+    let guard = vals.try_lock().map_err(|_| GenCamError::AccessViolation)?;
+    match guard.get(&name) {
+        Some(val) => Ok(val.clone()),
+        None => Err(GenCamError::PropertyError {
+            control: name,
+            error: PropertyError::NotFound,
+        }),
+    }
+}
+
+/// Write a property value of a dummy camera.
+fn set_value(
+    caps: &HashMap<GenCamCtrl, Property>,
+    vals: &Mutex<HashMap<GenCamCtrl, (PropertyValue, bool)>>,
+    capture_state: &CaptureState,
+    watchers: &Watchers,
+    name: GenCamCtrl,
+    value: &PropertyValue,
+    auto: bool,
+) -> GenCamResult<()> {
+    if capture_state.is_capturing(Ordering::Relaxed) {
+        return Err(GenCamError::ExposureInProgress);
+    }
+    if let Some(prop) = caps.get(&name) {
+        prop.check_writable()
+            .map_err(|error| GenCamError::PropertyError {
+                control: name,
+                error,
+            })?;
+    }
+    let mut guard = vals.try_lock().map_err(|_| GenCamError::AccessViolation)?;
+    match guard.get_mut(&name) {
+        Some(val) => {
+            *val = (value.clone(), auto);
+            drop(guard);
+            watchers.notify(name, value);
+            Ok(())
+        }
+        None => Err(GenCamError::PropertyError {
+            control: name,
+            error: PropertyError::NotFound,
+        }),
+    }
 }
 
 #[derive(Debug)]
@@ -293,76 +490,405 @@ pub struct GenCamDummy {
     name: String,
     vendor: String,
     caps: HashMap<GenCamCtrl, Property>,
-    vals: Mutex<HashMap<GenCamCtrl, (PropertyValue, bool)>>,
+    vals: Arc<Mutex<HashMap<GenCamCtrl, (PropertyValue, bool)>>>,
     capture_state: Arc<CaptureState>,
+    connected: Arc<AtomicBool>,
     // capturing: Arc<AtomicBool>,
     // imgready: Arc<AtomicBool>,
     roi: GenCamRoi,
     data: Vec<u8>,
+    pattern: DummyPattern,
+    failure: Option<GenCamError>,
+    failure_mode: FailureMode,
+    start_latency: Duration,
+    download_delay: Duration,
+    rng: StdRng,
+    subscribers: Arc<Subscribers>,
+    watchers: Arc<Watchers>,
+    frames: Arc<FrameCounter>,
+    stream: Option<DummyStream>,
+}
+
+/// The frame counter of a dummy camera, shared with its stream thread.
+///
+/// Like [`Subscribers`], this is kept out of the loom model.
+#[derive(Debug, Default)]
+struct FrameCounter(std::sync::Mutex<Option<FrameMeta>>);
+
+impl FrameCounter {
+    /// Record a frame downloaded at `host_timestamp`.
+    fn next(&self, host_timestamp: SystemTime) -> FrameMeta {
+        let mut last = self
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let meta = FrameMeta {
+            frame_id: last.map_or(0, |meta| meta.frame_id + 1),
+            hardware_timestamp: None,
+            host_timestamp,
+        };
+        *last = Some(meta);
+        meta
+    }
+
+    /// The last frame downloaded, if any.
+    fn last(&self) -> Option<FrameMeta> {
+        *self
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// The background thread of a streaming dummy camera, see [`GenCam::start_stream`].
+///
+/// Like [`Subscribers`], this is kept out of the loom model.
+#[derive(Debug)]
+struct DummyStream {
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    handle: std::thread::JoinHandle<()>,
+}
+
+/// Subscribers to the state of a dummy camera.
+///
+/// This is not part of the capture state machine, so it always uses the std mutex
+/// to keep it out of the loom model.
+#[derive(Debug, Default)]
+struct Subscribers(std::sync::Mutex<Vec<Sender<GenCamState>>>);
+
+impl Subscribers {
+    /// Send a state transition to all subscribers, dropping those that hung up.
+    fn notify(&self, state: GenCamState) {
+        if let Ok(mut subs) = self.0.lock() {
+            subs.retain(|tx| tx.send(state.clone()).is_ok());
+        }
+    }
+}
+
+/// Watchers of the property values of a dummy camera, see [`GenCam::watch_property`].
+#[derive(Debug, Default)]
+struct Watchers(std::sync::Mutex<Vec<(GenCamCtrl, Sender<PropertyValue>)>>);
+
+impl Watchers {
+    /// Send a new value to the watchers of `ctrl`, dropping those that hung up.
+    fn notify(&self, ctrl: GenCamCtrl, value: &PropertyValue) {
+        if let Ok(mut watchers) = self.0.lock() {
+            watchers.retain(|(watched, tx)| *watched != ctrl || tx.send(value.clone()).is_ok());
+        }
+    }
 }
 
 impl GenCamDummy {
-    fn set_property_impl(
-        &mut self,
-        name: crate::GenCamCtrl,
-        value: &crate::PropertyValue,
-        auto: bool,
-    ) -> GenCamResult<()> {
-        if self.capture_state.is_capturing(Ordering::Relaxed) {
-            return Err(GenCamError::ExposureInProgress);
+    /// The longest wait hinted by [`GenCam::poll_exposure`] while exposing.
+    const POLL_INTERVAL: Duration = Duration::from_millis(20);
+    /// The number of frames queued by [`GenCam::start_stream`] before dropping the oldest.
+    pub const STREAM_DEPTH: usize = 4;
+
+    /// Create a new dummy camera described by `descriptor`.
+    pub fn new(descriptor: &GenCamDescriptor) -> Self {
+        let mut caps = HashMap::new();
+        caps.insert(
+            GenCamCtrl::Exposure(ExposureCtrl::ExposureTime),
+            Property::new(
+                PropertyLims::Duration {
+                    min: Duration::from_millis(1),
+                    max: Duration::from_secs(60),
+                    step: Duration::from_millis(1),
+                    default: Duration::from_secs(1),
+                },
+                false,
+                false,
+            ),
+        );
+        caps.insert(
+            GenCamCtrl::Analog(AnalogCtrl::Gain),
+            Property::new(
+                PropertyLims::Float {
+                    min: 0.0,
+                    max: 100.0,
+                    step: 0.1,
+                    default: 0.0,
+                },
+                true,
+                false,
+            ),
+        );
+        let (width, height) = (1920, 1080);
+        for (ctrl, size) in [
+            (SensorCtrl::WidthMax, width),
+            (SensorCtrl::HeightMax, height),
+        ] {
+            caps.insert(
+                GenCamCtrl::Sensor(ctrl),
+                Property::new(
+                    PropertyLims::Unsigned {
+                        min: size,
+                        max: size,
+                        step: 1,
+                        default: size,
+                    },
+                    false,
+                    true,
+                ),
+            );
         }
-        let mut guard = self
-            .vals
-            .try_lock()
-            .map_err(|_| GenCamError::AccessViolation)?;
-        match guard.get_mut(&name) {
-            Some(val) => {
-                *val = (value.clone(), auto);
-                Ok(())
-            }
-            None => Err(GenCamError::PropertyError {
-                control: name,
-                error: PropertyError::NotFound,
+        caps.insert(
+            GenCamCtrl::Sensor(SensorCtrl::PixelFormat),
+            Property::new(
+                PropertyLims::PixelFmt {
+                    variants: vec![GenCamPixelBpp::Bpp8, GenCamPixelBpp::Bpp24],
+                    default: GenCamPixelBpp::Bpp24,
+                },
+                false,
+                false,
+            ),
+        );
+        let mut vals = HashMap::new();
+        vals.insert(
+            GenCamCtrl::Sensor(SensorCtrl::PixelFormat),
+            (PropertyValue::PixelFmt(GenCamPixelBpp::Bpp24), false),
+        );
+        vals.insert(
+            GenCamCtrl::Sensor(SensorCtrl::WidthMax),
+            (PropertyValue::Unsigned(width), false),
+        );
+        vals.insert(
+            GenCamCtrl::Sensor(SensorCtrl::HeightMax),
+            (PropertyValue::Unsigned(height), false),
+        );
+        vals.insert(
+            GenCamCtrl::Exposure(ExposureCtrl::ExposureTime),
+            (PropertyValue::Duration(Duration::from_secs(1)), false),
+        );
+        vals.insert(
+            GenCamCtrl::Analog(AnalogCtrl::Gain),
+            (PropertyValue::Float(0.0), false),
+        );
+        let mut this = GenCamDummy {
+            desc: descriptor.clone(),
+            name: descriptor.name.clone(),
+            vendor: descriptor.vendor.clone(),
+            caps,
+            vals: Arc::new(Mutex::new(vals)),
+            // capturing: Arc::new(AtomicBool::new(false)),
+            roi: GenCamRoi::default(),
+            data: Vec::new(),
+            // imgready: Arc::new(AtomicBool::new(false)),
+            capture_state: Arc::new(CaptureState::new()), // start: AtomicOptionInstant::none(),
+            connected: Arc::new(AtomicBool::new(true)),
+            pattern: DummyPattern::default(),
+            failure: None,
+            failure_mode: FailureMode::default(),
+            start_latency: Duration::ZERO,
+            download_delay: Duration::ZERO,
+            subscribers: Default::default(),
+            watchers: Default::default(),
+            frames: Default::default(),
+            stream: None,
+            rng: StdRng::seed_from_u64(if cfg!(any(miri, feature = "loom")) {
+                0
+            } else {
+                thread_rng().r#gen()
             }),
+        };
+        this.roi = this
+            .full_frame()
+            .expect("the dummy camera reports its sensor size");
+        this
+    }
+
+    /// Create a new dummy camera whose [`DummyPattern::Noise`] frames are generated
+    /// from `seed`, making the captured images reproducible.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut this = Self::new(&dummy_descriptor());
+        this.rng = StdRng::seed_from_u64(seed);
+        this
+    }
+
+    /// Set the pattern of the generated images.
+    pub fn set_pattern(&mut self, pattern: DummyPattern) {
+        self.pattern = pattern;
+    }
+
+    /// Make the exposure start `latency` after [`GenCam::start_exposure`] returns.
+    ///
+    /// Until the exposure starts, the camera reports [`GenCamState::Exposing`] with an
+    /// unknown exposure time. The default is no latency.
+    pub fn set_start_latency(&mut self, latency: Duration) {
+        self.start_latency = latency;
+    }
+
+    /// Make downloading a finished exposure take `delay`.
+    ///
+    /// During the download, [`GenCam::poll_exposure`] asks the caller to wait, and the
+    /// camera reports [`GenCamState::Downloading`] with the percentage downloaded. The
+    /// default is no delay, where the camera goes straight from
+    /// [`GenCamState::ExposureFinished`] to returning the image.
+    pub fn set_download_delay(&mut self, delay: Duration) {
+        self.download_delay = delay;
+    }
+
+    /// Make capture operations fail with `error`, or succeed again with `None`.
+    ///
+    /// The error is returned by [`GenCam::start_exposure`], or by
+    /// [`GenCam::poll_exposure`] once the exposure has finished, instead of downloading
+    /// the image. See [`GenCamDummy::set_failure_mode`] for how often it is returned.
+    ///
+    /// A stream started with [`GenCam::start_stream`] takes over the failure, and sends
+    /// the error in place of a frame. Setting the failure while streaming only affects
+    /// capture operations after the stream is stopped.
+    pub fn set_failure(&mut self, error: Option<GenCamError>) {
+        self.failure = error;
+    }
+
+    /// Set how often the error set with [`GenCamDummy::set_failure`] is returned.
+    pub fn set_failure_mode(&mut self, mode: FailureMode) {
+        self.failure_mode = mode;
+    }
+
+    /// Return the finished image, or the injected failure.
+    fn download(&mut self) -> PollExposure<'_> {
+        match self.take_failure() {
+            Some(e) => PollExposure::Ready(Err(e)),
+            None => PollExposure::Ready(self.make_dummy_image()),
+        }
+    }
+
+    /// Get the error to fail the current capture operation with, if any.
+    fn take_failure(&mut self) -> Option<GenCamError> {
+        match self.failure_mode {
+            FailureMode::Once => self.failure.take(),
+            FailureMode::Always => self.failure.clone(),
+        }
+    }
+
+    /// Simulate the camera being unplugged.
+    ///
+    /// Any exposure or stream in progress is cancelled, [`GenCam::start_exposure`]
+    /// returns [`GenCamError::CameraRemoved`] and the camera reports an errored state
+    /// until [`GenCam::reconnect`] is called. Property values are kept.
+    pub fn simulate_removal(&mut self) {
+        if self.stream.is_some() {
+            _ = self.stop_stream();
+        }
+        if self.capture_state.cancel_capture().is_ok() {
+            self.subscribers.notify(GenCamState::Idle);
+        }
+        self.connected.store(false, Ordering::Release);
+    }
+
+    /// Get the region of interest covering the whole sensor, with the sensor size read
+    /// from [`SensorCtrl::WidthMax`] and [`SensorCtrl::HeightMax`].
+    fn full_frame(&self) -> GenCamResult<GenCamRoi> {
+        let (width, height) = self.binned_size()?;
+        validate_roi(&GenCamRoi::default(), width, height)
+    }
+
+    /// Get the size of the sensor in binned pixels, which bounds the region of interest.
+    fn binned_size(&self) -> GenCamResult<(u16, u16)> {
+        let (width, height) = self.sensor_size()?;
+        let (horz, vert) = match self.get_binning() {
+            Ok(bins) => bins,
+            Err(GenCamError::PropertyError {
+                error: PropertyError::NotFound,
+                ..
+            }) => (1, 1),
+            Err(e) => return Err(e),
+        };
+        let binned = |size: u32, bin: u32| u16::try_from(size / bin.max(1)).unwrap_or(u16::MAX);
+        Ok((binned(width, horz), binned(height, vert)))
+    }
+
+    /// Get the pattern of the generated images.
+    pub fn get_pattern(&self) -> DummyPattern {
+        self.pattern
+    }
+
+    /// Create the camera capturing on the stream thread, which shares the capture state
+    /// and property values with this camera.
+    fn stream_camera(&mut self) -> Self {
+        let failure = self.take_failure();
+        GenCamDummy {
+            desc: self.desc.clone(),
+            name: self.name.clone(),
+            vendor: self.vendor.clone(),
+            caps: self.caps.clone(),
+            vals: self.vals.clone(),
+            capture_state: self.capture_state.clone(),
+            // the stream camera being dropped does not disconnect this one
+            connected: Arc::new(AtomicBool::new(true)),
+            roi: self.roi,
+            data: Vec::new(),
+            pattern: self.pattern,
+            failure,
+            failure_mode: self.failure_mode,
+            start_latency: self.start_latency,
+            download_delay: self.download_delay,
+            rng: StdRng::seed_from_u64(self.rng.r#gen()),
+            subscribers: self.subscribers.clone(),
+            watchers: self.watchers.clone(),
+            frames: self.frames.clone(),
+            stream: None,
         }
     }
+
     fn make_dummy_image(&mut self) -> GenCamResult<GenericImageRef<'_>> {
-        // If we're on miri, rng calls are stupidly slow. You shouldn't care
-        // about the data for the dummy camera anyway. If we're using loom,
-        // we need to remove the rng call no matter what since loom requires
-        // determinism.
-        if cfg!(any(miri, feature = "loom")) {
-            /// RFC 1149.5 specifies 4 as the standard IEEE-vetted random number.
-            const fn xkcd_221() -> u8 {
-                // Chosen by fair dice roll.
-                // Guaranteed to be random.
-                4
+        let cspace = self.color_space()?;
+        let channels = crate::channels_for(&cspace).unwrap_or(1);
+        let width = (self.roi.width as usize).max(1);
+        self.data.resize(self.frame_buffer_len()?, 0);
+        match self.pattern {
+            // If we're on miri, rng calls are stupidly slow. You shouldn't care
+            // about the data for the dummy camera anyway. If we're using loom,
+            // we need to remove the rng call no matter what since loom requires
+            // determinism.
+            DummyPattern::Noise if cfg!(any(miri, feature = "loom")) => {
+                /// RFC 1149.5 specifies 4 as the standard IEEE-vetted random number.
+                const fn xkcd_221() -> u8 {
+                    // Chosen by fair dice roll.
+                    // Guaranteed to be random.
+                    4
+                }
+                self.data.as_mut_slice().fill(xkcd_221());
+            }
+            DummyPattern::Noise => self.rng.fill(self.data.as_mut_slice()),
+            DummyPattern::SolidValue(value) => self.data.as_mut_slice().fill(value),
+            DummyPattern::Gradient => {
+                let span = (width - 1).max(1);
+                for (idx, pixel) in self.data.chunks_exact_mut(channels).enumerate() {
+                    let x = idx % width;
+                    pixel.fill((x.min(span) * 255 / span) as u8);
+                }
+            }
+            DummyPattern::Checkerboard { size } => {
+                let size = (size as usize).max(1);
+                for (idx, pixel) in self.data.chunks_exact_mut(channels).enumerate() {
+                    let (x, y) = (idx % width, idx / width);
+                    let white = (x / size + y / size) % 2 == 1;
+                    pixel.fill(if white { 255 } else { 0 });
+                }
             }
-            self.data.as_mut_slice().fill(xkcd_221());
-        } else {
-            thread_rng().fill(self.data.as_mut_slice());
         }
 
         let img = ImageRef::new(
             &mut self.data,
             self.roi.width as _,
             self.roi.height as _,
-            refimage::ColorSpace::Rgb,
+            cspace,
         )
         .map_err(|e| GenCamError::InvalidImageType(e.to_string()))?;
         let img = DynamicImageRef::from(img);
-        let mut img = GenericImageRef::new(
-            if cfg!(miri) {
-                // miri doesn't support getting system time
-                SystemTime::UNIX_EPOCH
-            } else {
-                SystemTime::now()
-            },
-            img,
-        );
-        img.insert_key("XOFST", self.roi.x_min as u32)
+        let now = if cfg!(miri) {
+            // miri doesn't support getting system time
+            SystemTime::UNIX_EPOCH
+        } else {
+            SystemTime::now()
+        };
+        self.frames.next(now);
+        let mut img = GenericImageRef::new(now, img);
+        img.insert_key(metadata_keys::X_OFFSET, self.roi.x_min as u32)
             .map_err(|e| GenCamError::InvalidImageType(format!("Error inserting key: {e}")))?;
-        img.insert_key("YOFST", self.roi.y_min as u32)
+        img.insert_key(metadata_keys::Y_OFFSET, self.roi.y_min as u32)
             .map_err(|e| GenCamError::InvalidImageType(format!("Error inserting key: {e}")))?;
         Ok(img)
     }
@@ -370,7 +896,15 @@ impl GenCamDummy {
 
 impl GenCam for GenCamDummy {
     fn info_handle(&self) -> Option<crate::AnyGenCamInfo> {
-        None
+        Some(Box::new(GenCamDummyInfo {
+            name: self.name.clone(),
+            caps: self.caps.clone(),
+            vals: self.vals.clone(),
+            capture_state: self.capture_state.clone(),
+            connected: self.connected.clone(),
+            subscribers: self.subscribers.clone(),
+            watchers: self.watchers.clone(),
+        }))
     }
 
     fn info(&self) -> GenCamResult<&GenCamDescriptor> {
@@ -382,7 +916,7 @@ impl GenCam for GenCamDummy {
     }
 
     fn camera_ready(&self) -> bool {
-        true
+        self.connected.load(Ordering::Acquire)
     }
 
     fn camera_name(&self) -> &str {
@@ -394,18 +928,19 @@ impl GenCam for GenCamDummy {
     }
 
     fn get_property(&self, name: crate::GenCamCtrl) -> GenCamResult<(crate::PropertyValue, bool)> {
-        // deadlock me not
+        get_value(&self.vals, name)
+    }
+
+    fn snapshot_properties(&self) -> GenCamResult<PropertySnapshot> {
         let guard = self
             .vals
             .try_lock()
             .map_err(|_| GenCamError::AccessViolation)?;
-        match guard.get(&name) {
-            Some(val) => Ok(val.clone()),
-            None => Err(GenCamError::PropertyError {
-                control: name,
-                error: PropertyError::NotFound,
-            }),
-        }
+        Ok(self
+            .caps
+            .iter()
+            .map(|(ctrl, prop)| (*ctrl, (prop.clone(), guard.get(ctrl).cloned())))
+            .collect())
     }
 
     fn set_property(
@@ -413,7 +948,15 @@ impl GenCam for GenCamDummy {
         name: crate::GenCamCtrl,
         value: &crate::PropertyValue,
     ) -> GenCamResult<()> {
-        self.set_property_impl(name, value, false)
+        set_value(
+            &self.caps,
+            &self.vals,
+            &self.capture_state,
+            &self.watchers,
+            name,
+            value,
+            false,
+        )
     }
 
     fn set_property_auto(
@@ -421,11 +964,21 @@ impl GenCam for GenCamDummy {
         name: crate::GenCamCtrl,
         value: &crate::PropertyValue,
     ) -> GenCamResult<()> {
-        self.set_property_impl(name, value, true)
+        set_value(
+            &self.caps,
+            &self.vals,
+            &self.capture_state,
+            &self.watchers,
+            name,
+            value,
+            true,
+        )
     }
 
     fn cancel_capture(&self) -> GenCamResult<()> {
-        self.capture_state.cancel_capture()
+        self.capture_state.cancel_capture()?;
+        self.subscribers.notify(GenCamState::Idle);
+        Ok(())
     }
 
     fn is_capturing(&self) -> bool {
@@ -433,7 +986,19 @@ impl GenCam for GenCamDummy {
     }
 
     fn start_exposure(&mut self) -> GenCamResult<()> {
-        let now = self.capture_state.start_capture()?;
+        if self.stream.is_some() {
+            return Err(GenCamError::ExposureInProgress);
+        }
+        if !self.camera_ready() {
+            return Err(GenCamError::CameraRemoved);
+        }
+        if self.capture_state.is_capturing(Ordering::Relaxed) {
+            return Err(GenCamError::ExposureInProgress);
+        }
+        if let Some(e) = self.take_failure() {
+            return Err(e);
+        }
+        let now = self.capture_state.start_capture(self.start_latency)?;
         let (exp, _) = self.get_property(GenCamCtrl::Exposure(ExposureCtrl::ExposureTime))?;
 
         let exp: Duration = exp.try_into().map_err(|e| GenCamError::PropertyError {
@@ -441,22 +1006,27 @@ impl GenCam for GenCamDummy {
             error: e,
         })?;
 
+        // the exposure time is unknown until the exposure starts, see `set_start_latency`
+        let elapsed = self.start_latency.is_zero().then(|| now.elapsed());
+        self.subscribers.notify(GenCamState::Exposing(elapsed));
         let state = self.capture_state.clone();
+        let subscribers = self.subscribers.clone();
         thread::spawn(move || {
             loop {
                 if !state.is_capturing(Ordering::Relaxed) {
                     break;
                 }
-                if now.elapsed() >= exp {
-                    _ = state.mark_ready();
+                let elapsed = now.elapsed();
+                if elapsed >= exp {
+                    if state.mark_ready().is_ok() {
+                        subscribers.notify(GenCamState::ExposureFinished);
+                    }
                     break;
                 }
                 if cfg!(feature = "loom") {
                     // thread::yield_now();
                 } else {
-                    std::thread::sleep(Duration::from_secs_f32(
-                        thread_rng().gen_range(1.0..15.0) * 0.001,
-                    ));
+                    state.wait_timeout(exp - elapsed);
                 }
             }
         });
@@ -476,37 +1046,1558 @@ impl GenCam for GenCamDummy {
                 })?;
             Ok(total_exposure_time.saturating_sub(time))
         }
+        if self.stream.is_some() {
+            // the frames belong to the stream thread
+            return PollExposure::Ready(Err(GenCamError::ExposureInProgress));
+        }
+        if self.capture_state.take_cancelled() {
+            return PollExposure::Ready(Err(GenCamError::Cancelled));
+        }
         match self.capture_state.get_state() {
+            // the wait is capped so that blocking captures notice a cancellation promptly
             GenCamState::Exposing(Some(time)) => match get_exposure_time_remaining(self, time) {
-                Ok(time) => PollExposure::Wait(time),
+                Ok(time) => PollExposure::Wait(time.min(Self::POLL_INTERVAL)),
                 Err(e) => PollExposure::Ready(Err(e)),
             },
-            GenCamState::Exposing(None) => PollExposure::Soon,
-            GenCamState::ExposureFinished => match self.make_dummy_image() {
-                Ok(img) => PollExposure::Ready(Ok(img)),
-                Err(e) => PollExposure::Ready(Err(e)),
+            GenCamState::Exposing(None) => match self.capture_state.start_delay() {
+                Some(delay) => PollExposure::Wait(delay.min(Self::POLL_INTERVAL)),
+                None => PollExposure::Soon,
             },
+            GenCamState::ExposureFinished
+                if !self.download_delay.is_zero()
+                    && self.capture_state.start_download(self.download_delay) =>
+            {
+                self.subscribers.notify(GenCamState::Downloading(Some(0)));
+                PollExposure::Wait(self.download_delay.min(Self::POLL_INTERVAL))
+            }
+            // subscribers see the progress every time the download is polled
+            state @ GenCamState::Downloading(_) => {
+                let remaining = self.capture_state.download_remaining();
+                if remaining.is_zero() {
+                    self.subscribers.notify(GenCamState::Downloading(Some(100)));
+                    self.capture_state.finish_download();
+                    self.subscribers.notify(GenCamState::ExposureFinished);
+                    self.download()
+                } else {
+                    self.subscribers.notify(state);
+                    PollExposure::Wait(remaining.min(Self::POLL_INTERVAL))
+                }
+            }
+            GenCamState::ExposureFinished => self.download(),
             _ => PollExposure::Ready(Err(GenCamError::ExposureNotStarted)),
         }
     }
 
     fn camera_state(&self) -> GenCamResult<GenCamState> {
+        if !self.camera_ready() {
+            return Ok(GenCamState::Errored(GenCamError::CameraRemoved));
+        }
         Ok(self.capture_state.get_state())
     }
 
-    fn set_roi(&mut self, roi: &GenCamRoi) -> GenCamResult<&GenCamRoi> {
-        let mut roi = *roi;
-        roi.x_min = roi.x_min.max(1);
-        roi.y_min = roi.y_min.max(1);
-        roi.width = roi.width.max(1920);
-        roi.height = roi.height.max(1080);
-        roi.x_min = roi.x_min.min(1920 - roi.width);
-        roi.y_min = roi.y_min.min(1080 - roi.height);
-        self.roi = roi;
-        Ok(&self.roi)
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            streaming: true,
+            ..Capabilities::from_properties(&self.caps)
+        }
     }
 
-    fn get_roi(&self) -> &GenCamRoi {
-        &self.roi
+    fn color_space(&self) -> GenCamResult<refimage::ColorSpace> {
+        let ctrl = GenCamCtrl::Sensor(SensorCtrl::PixelFormat);
+        match self.get_property(ctrl)?.0 {
+            PropertyValue::PixelFmt(GenCamPixelBpp::Bpp8) => Ok(refimage::ColorSpace::Gray),
+            PropertyValue::PixelFmt(GenCamPixelBpp::Bpp24) => Ok(refimage::ColorSpace::Rgb),
+            _ => Err(GenCamError::PropertyError {
+                control: ctrl,
+                error: PropertyError::ValueNotSupported,
+            }),
+        }
+    }
+
+    fn subscribe_state(&self) -> GenCamResult<Receiver<GenCamState>> {
+        let (tx, rx) = channel();
+        self.subscribers
+            .0
+            .lock()
+            .map_err(|_| GenCamError::AccessViolation)?
+            .push(tx);
+        Ok(rx)
+    }
+
+    fn watch_property(&self, ctrl: GenCamCtrl) -> GenCamResult<Receiver<PropertyValue>> {
+        if !self.caps.contains_key(&ctrl) {
+            return Err(GenCamError::PropertyError {
+                control: ctrl,
+                error: PropertyError::NotFound,
+            });
+        }
+        let (tx, rx) = channel();
+        self.watchers
+            .0
+            .lock()
+            .map_err(|_| GenCamError::AccessViolation)?
+            .push((ctrl, tx));
+        Ok(rx)
+    }
+
+    fn peek_buffer(&self) -> GenCamResult<&[u8]> {
+        if self.data.is_empty() {
+            return Err(GenCamError::ExposureNotStarted);
+        }
+        Ok(&self.data)
+    }
+
+    fn last_frame_meta(&self) -> GenCamResult<Option<FrameMeta>> {
+        Ok(self.frames.last())
+    }
+
+    fn start_stream(&mut self) -> GenCamResult<FrameStream> {
+        if self.stream.is_some() || self.is_capturing() {
+            return Err(GenCamError::ExposureInProgress);
+        }
+        let (tx, rx) = crate::stream::stream_channel(Self::STREAM_DEPTH);
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut cam = self.stream_camera();
+        let handle = std::thread::spawn({
+            let stop = stop.clone();
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let frame = cam.capture().map(GenericImageOwned::from).and_then(|img| {
+                        let meta = cam.frames.last().ok_or(GenCamError::AccessViolation)?;
+                        Ok((meta, img))
+                    });
+                    if stop.load(Ordering::Relaxed) || tx.send(frame).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        self.stream = Some(DummyStream { stop, handle });
+        Ok(rx)
+    }
+
+    fn stop_stream(&mut self) -> GenCamResult<()> {
+        let DummyStream { stop, handle } =
+            self.stream.take().ok_or(GenCamError::ExposureNotStarted)?;
+        stop.store(true, Ordering::Relaxed);
+        // the thread may be between frames, so keep cancelling until it notices
+        while !handle.is_finished() {
+            if self.capture_state.cancel_capture().is_ok() {
+                self.subscribers.notify(GenCamState::Idle);
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        self.capture_state.take_cancelled();
+        handle
+            .join()
+            .map_err(|_| GenCamError::GeneralError("Stream thread panicked".into()))
+    }
+
+    fn reconnect(&mut self) -> GenCamResult<()> {
+        // the dummy has no device to reopen, so this only clears the error state
+        self.capture_state.take_cancelled();
+        self.connected.store(true, Ordering::Release);
+        self.subscribers.notify(GenCamState::Idle);
+        Ok(())
+    }
+
+    fn reset(&mut self) -> GenCamResult<()> {
+        if self.stream.is_some() {
+            self.stop_stream()?;
+        }
+        self.capture_state.reset();
+        self.failure = None;
+        self.connected.store(true, Ordering::Release);
+        self.roi = self.full_frame()?;
+        self.subscribers.notify(GenCamState::Idle);
+        Ok(())
+    }
+
+    fn set_roi(&mut self, roi: &GenCamRoi) -> GenCamResult<&GenCamRoi> {
+        if self.stream.is_some() {
+            return Err(GenCamError::ExposureInProgress);
+        }
+        let (width, height) = self.binned_size()?;
+        self.roi = validate_roi(roi, width, height)?;
+        Ok(&self.roi)
+    }
+
+    fn get_roi(&self) -> &GenCamRoi {
+        &self.roi
+    }
+}
+
+impl Drop for GenCamDummy {
+    fn drop(&mut self) {
+        if self.stream.is_some() {
+            _ = self.stop_stream();
+        }
+        self.connected.store(false, Ordering::Release);
+    }
+}
+
+#[derive(Debug)]
+/// A [`GenCamInfo`] handle to a [`GenCamDummy`], obtained with [`GenCam::info_handle`].
+///
+/// The handle shares the capture state and property values with the camera, and
+/// reports [`GenCamInfo::is_connected`] as `false` once the camera is dropped.
+///
+/// # Example
+/// ```
+/// use generic_camera::dummy::GenCamDriverDummy;
+/// use generic_camera::GenCamDriver;
+///
+/// let mut camera = GenCamDriverDummy::default().connect_first_device().unwrap();
+/// let info = camera.info_handle().unwrap();
+/// camera.start_exposure().unwrap();
+/// assert!(info.is_capturing());
+/// info.cancel_capture().unwrap();
+/// assert!(!camera.is_capturing());
+/// ```
+pub struct GenCamDummyInfo {
+    name: String,
+    caps: HashMap<GenCamCtrl, Property>,
+    vals: Arc<Mutex<HashMap<GenCamCtrl, (PropertyValue, bool)>>>,
+    capture_state: Arc<CaptureState>,
+    connected: Arc<AtomicBool>,
+    subscribers: Arc<Subscribers>,
+    watchers: Arc<Watchers>,
+}
+
+impl GenCamInfo for GenCamDummyInfo {
+    fn camera_ready(&self) -> bool {
+        self.is_connected()
+    }
+
+    fn camera_name(&self) -> &str {
+        &self.name
+    }
+
+    fn cancel_capture(&self) -> GenCamResult<()> {
+        self.capture_state.cancel_capture()?;
+        self.subscribers.notify(GenCamState::Idle);
+        Ok(())
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.capture_state.is_capturing(Ordering::Relaxed)
+    }
+
+    fn camera_state(&self) -> GenCamResult<GenCamState> {
+        Ok(self.capture_state.get_state())
+    }
+
+    fn list_properties(&self) -> &HashMap<GenCamCtrl, Property> {
+        &self.caps
+    }
+
+    fn get_property(&self, name: GenCamCtrl) -> GenCamResult<(PropertyValue, bool)> {
+        get_value(&self.vals, name)
+    }
+
+    fn set_property(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()> {
+        set_value(
+            &self.caps,
+            &self.vals,
+            &self.capture_state,
+            &self.watchers,
+            name,
+            value,
+            false,
+        )
+    }
+
+    fn set_property_auto(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()> {
+        set_value(
+            &self.caps,
+            &self.vals,
+            &self.capture_state,
+            &self.watchers,
+            name,
+            value,
+            true,
+        )
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(all(test, not(feature = "loom")))]
+mod test {
+    use super::*;
+    use crate::controls::{DeviceCtrl, FrameTimeCtrl};
+    use crate::{BracketSpacing, CameraConfig, Capture, CaptureSequence, CoolerStatus};
+    use refimage::{GenericValue, ImageProps};
+
+    fn make_dummy() -> crate::AnyGenCam {
+        GenCamDriverDummy::default().connect_first_device().unwrap()
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn property_schema() {
+        let schema = crate::property::property_json_schema();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let cam = make_dummy();
+        let exposure = cam
+            .list_properties()
+            .get(&GenCamCtrl::Exposure(ExposureCtrl::ExposureTime))
+            .unwrap();
+        let mut value = serde_json::to_value(exposure).unwrap();
+        assert!(validator.is_valid(&value));
+        for prop in cam.list_properties().values() {
+            assert!(validator.is_valid(&serde_json::to_value(prop).unwrap()));
+        }
+        value["prop"]["Duration"]["min"] = serde_json::json!(-1);
+        assert!(!validator.is_valid(&value));
+    }
+
+    #[test]
+    fn reconnect() {
+        let mut cam = GenCamDummy::with_seed(42);
+        let info = cam.info_handle().unwrap();
+        cam.set_exposure(Duration::from_millis(2)).unwrap();
+        cam.start_exposure().unwrap();
+        cam.simulate_removal();
+        assert!(!cam.camera_ready() && !info.is_connected());
+        assert!(matches!(
+            cam.camera_state(),
+            Ok(GenCamState::Errored(GenCamError::CameraRemoved))
+        ));
+        assert!(matches!(
+            cam.start_exposure(),
+            Err(GenCamError::CameraRemoved)
+        ));
+
+        cam.reconnect().unwrap();
+        assert!(cam.camera_ready() && info.is_connected());
+        assert_eq!(cam.camera_state().unwrap(), GenCamState::Idle);
+        assert_eq!(cam.get_exposure().unwrap(), Duration::from_millis(2));
+        cam.capture().unwrap();
+    }
+
+    #[test]
+    fn failure() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::SolidValue(3));
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.set_failure(Some(GenCamError::TimedOut));
+        assert_eq!(cam.capture().err(), Some(GenCamError::TimedOut));
+        cam.capture().unwrap();
+
+        // a failure set during the exposure is returned instead of the image
+        cam.start_exposure().unwrap();
+        cam.set_failure(Some(GenCamError::TimedOut));
+        while cam.camera_state().unwrap() != GenCamState::ExposureFinished {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(matches!(
+            cam.poll_exposure(),
+            PollExposure::Ready(Err(GenCamError::TimedOut))
+        ));
+
+        cam.set_failure_mode(FailureMode::Always);
+        cam.set_failure(Some(GenCamError::Cancelled));
+        for _ in 0..3 {
+            assert_eq!(cam.start_exposure(), Err(GenCamError::Cancelled));
+        }
+        cam.set_failure(None);
+        cam.capture().unwrap();
+        assert!(cam.data.iter().all(|&v| v == 3));
+
+        // the stream sends the failure in place of a frame
+        cam.set_failure_mode(FailureMode::Once);
+        cam.set_failure(Some(GenCamError::TimedOut));
+        let rx = cam.start_stream().unwrap();
+        let next = || rx.recv_timeout(Duration::from_secs(1)).unwrap().map(|_| ());
+        assert_eq!(next(), Err(GenCamError::TimedOut));
+        assert_eq!(next(), Ok(()));
+        cam.stop_stream().unwrap();
+        // the failure was taken by the stream
+        cam.capture().unwrap();
+    }
+
+    #[test]
+    fn slow_download() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.set_start_latency(Duration::from_millis(5));
+        cam.set_download_delay(Duration::from_millis(200));
+        let start = Instant::now();
+        cam.start_exposure().unwrap();
+        // the exposure has visibly started, but not counting yet
+        assert_eq!(cam.camera_state(), Ok(GenCamState::Exposing(None)));
+        assert!(matches!(cam.poll_exposure(), PollExposure::Wait(_)));
+        while cam.camera_state().unwrap() != GenCamState::ExposureFinished {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(start.elapsed() >= Duration::from_millis(6));
+        assert!(matches!(cam.poll_exposure(), PollExposure::Wait(_)));
+        assert!(matches!(
+            cam.camera_state(),
+            Ok(GenCamState::Downloading(Some(pct))) if pct < 100
+        ));
+        let img = loop {
+            if let PollExposure::Ready(img) = cam.poll_exposure() {
+                break img;
+            }
+            thread::sleep(Duration::from_millis(5));
+        };
+        assert!(img.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(cam.camera_state().unwrap(), GenCamState::ExposureFinished);
+    }
+
+    #[test]
+    fn download_progress() {
+        let mut cam = GenCamDummy::with_seed(42);
+        let info = cam.info_handle().unwrap();
+        let rx = cam.subscribe_state().unwrap();
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.set_download_delay(Duration::from_millis(100));
+        cam.start_exposure().unwrap();
+        while info.camera_state().unwrap() != GenCamState::ExposureFinished {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(matches!(cam.poll_exposure(), PollExposure::Wait(_)));
+
+        // the progress only depends on the time spent downloading
+        let mut progress = vec![];
+        loop {
+            match info.camera_state().unwrap() {
+                GenCamState::Downloading(Some(pct)) => {
+                    if progress.last() != Some(&pct) {
+                        progress.push(pct);
+                    }
+                    if pct == 100 {
+                        break;
+                    }
+                }
+                state => panic!("unexpected state {state:?}"),
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        // how many samples are taken depends on the scheduler
+        assert!(progress.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(progress.last(), Some(&100));
+        assert!(matches!(cam.poll_exposure(), PollExposure::Ready(Ok(_))));
+        assert_eq!(info.camera_state().unwrap(), GenCamState::ExposureFinished);
+
+        let states: Vec<_> = rx.try_iter().collect();
+        assert_eq!(
+            &states[states.len() - 3..],
+            &[
+                GenCamState::Downloading(Some(0)),
+                GenCamState::Downloading(Some(100)),
+                GenCamState::ExposureFinished
+            ]
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_exposure(Duration::from_secs(1)).unwrap();
+        cam.set_roi(&GenCamRoi {
+            x_min: 10,
+            y_min: 10,
+            width: 100,
+            height: 100,
+        })
+        .unwrap();
+        cam.start_exposure().unwrap();
+        cam.cancel_capture().unwrap();
+        cam.set_failure(Some(GenCamError::TimedOut));
+
+        cam.reset().unwrap();
+        assert_eq!(cam.camera_state().unwrap(), GenCamState::Idle);
+        assert!(!cam.is_capturing());
+        assert_eq!(
+            cam.get_roi(),
+            &GenCamRoi {
+                x_min: 0,
+                y_min: 0,
+                width: 1920,
+                height: 1080
+            }
+        );
+        // the cancellation was cleared along with the failure
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.capture().unwrap();
+    }
+
+    #[test]
+    fn color_space() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        assert_eq!(cam.color_space(), Ok(refimage::ColorSpace::Rgb));
+        assert_eq!(cam.frame_buffer_len(), Ok(1920 * 1080 * 3));
+        cam.capture().unwrap();
+        assert_eq!(cam.data.len(), 1920 * 1080 * 3);
+
+        let ctrl = GenCamCtrl::Sensor(SensorCtrl::PixelFormat);
+        cam.set_property(ctrl, &GenCamPixelBpp::Bpp8.into())
+            .unwrap();
+        assert_eq!(cam.color_space(), Ok(refimage::ColorSpace::Gray));
+        let img = cam.capture().unwrap();
+        assert_eq!(img.color_space(), refimage::ColorSpace::Gray);
+        assert_eq!(cam.data.len(), 1920 * 1080);
+    }
+
+    #[test]
+    fn auto_capable_controls() {
+        let cam = make_dummy();
+        let auto = cam.auto_capable_controls();
+        assert!(auto.contains(&GenCamCtrl::Analog(AnalogCtrl::Gain)));
+        assert!(!auto.contains(&GenCamCtrl::Exposure(ExposureCtrl::ExposureTime)));
+    }
+
+    #[test]
+    fn set_auto() {
+        let mut cam = make_dummy();
+        let gain = GenCamCtrl::Analog(AnalogCtrl::Gain);
+        cam.set_property(gain, &PropertyValue::Float(5.0)).unwrap();
+        cam.set_auto(gain, true).unwrap();
+        assert_eq!(
+            cam.get_property(gain),
+            Ok((PropertyValue::Float(5.0), true))
+        );
+        cam.set_auto(gain, false).unwrap();
+        assert_eq!(
+            cam.get_property(gain),
+            Ok((PropertyValue::Float(5.0), false))
+        );
+
+        let exposure = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        assert_eq!(
+            cam.set_auto(exposure, true),
+            Err(GenCamError::PropertyError {
+                control: exposure,
+                error: PropertyError::AutoNotSupported,
+            })
+        );
+        assert!(!cam.get_property(exposure).unwrap().1);
+    }
+
+    #[test]
+    fn capabilities() {
+        let caps = make_dummy().capabilities();
+        assert!(caps.exposure && caps.streaming && caps.auto_gain);
+        assert!(!caps.cooling && !caps.auto_exposure && !caps.hardware_trigger);
+    }
+
+    #[test]
+    fn wait_for_state() {
+        let mut cam = GenCamDummy::with_seed(42);
+        let (timeout, poll) = (Duration::from_secs(1), Duration::from_millis(1));
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        cam.start_exposure().unwrap();
+        assert_eq!(
+            cam.wait_for_state(&|s| *s == GenCamState::ExposureFinished, timeout, poll),
+            Ok(GenCamState::ExposureFinished)
+        );
+
+        cam.set_exposure(Duration::from_secs(1)).unwrap();
+        cam.start_exposure().unwrap();
+        assert_eq!(
+            cam.wait_for_state(&GenCamState::is_idle, Duration::from_millis(5), poll),
+            Err(GenCamError::TimedOut)
+        );
+        cam.simulate_removal();
+        assert_eq!(
+            cam.wait_for_state(&GenCamState::is_idle, timeout, poll),
+            Err(GenCamError::CameraRemoved)
+        );
+    }
+
+    #[test]
+    fn seeded_patterns() {
+        fn frame(pattern: DummyPattern) -> Vec<u8> {
+            let mut cam = GenCamDummy::with_seed(42);
+            cam.set_pattern(pattern);
+            cam.set_exposure(Duration::from_millis(1)).unwrap();
+            cam.capture().unwrap();
+            std::mem::take(&mut cam.data)
+        }
+        assert_eq!(frame(DummyPattern::Noise), frame(DummyPattern::Noise));
+        let gradient = frame(DummyPattern::Gradient);
+        assert_eq!(gradient, frame(DummyPattern::Gradient));
+        assert_eq!(&gradient[..3], &[0, 0, 0]);
+        assert_eq!(&gradient[1919 * 3..1920 * 3], &[255, 255, 255]);
+        assert!(frame(DummyPattern::SolidValue(7)).iter().all(|&v| v == 7));
+        let checkers = frame(DummyPattern::Checkerboard { size: 2 });
+        assert_eq!(checkers[0], 0);
+        assert_eq!(checkers[2 * 3], 255);
+        assert_eq!(checkers[1920 * 2 * 3], 255);
+    }
+
+    #[test]
+    fn image_matches_roi() {
+        let mut cam = GenCamDummy::with_seed(0);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.set_roi(&GenCamRoi {
+            x_min: 100,
+            y_min: 100,
+            width: 640,
+            height: 480,
+        })
+        .unwrap();
+        let img = cam.capture().unwrap();
+        assert_eq!((img.width(), img.height()), (640, 480));
+        assert_eq!(img.color_space(), refimage::ColorSpace::Rgb);
+        assert_eq!(cam.data.len(), 640 * 480 * 3);
+
+        cam.set_property(
+            GenCamCtrl::Sensor(SensorCtrl::PixelFormat),
+            &PropertyValue::PixelFmt(GenCamPixelBpp::Bpp8),
+        )
+        .unwrap();
+        let img = cam.capture().unwrap();
+        assert_eq!((img.width(), img.height()), (640, 480));
+        assert_eq!(img.color_space(), refimage::ColorSpace::Gray);
+        assert_eq!(cam.data.len(), 640 * 480);
+    }
+
+    #[test]
+    fn subscribe_state() {
+        let mut cam = make_dummy();
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        let rx = cam.subscribe_state().unwrap();
+        cam.start_exposure().unwrap();
+        assert!(rx.recv().unwrap().is_exposing());
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(1)),
+            Ok(GenCamState::ExposureFinished)
+        );
+        drop(rx);
+        cam.capture().unwrap();
+
+        let rx = cam.subscribe_state().unwrap();
+        cam.set_exposure(Duration::from_secs(10)).unwrap();
+        cam.start_exposure().unwrap();
+        cam.cancel_capture().unwrap();
+        assert!(rx.recv().unwrap().is_exposing());
+        assert_eq!(rx.recv(), Ok(GenCamState::Idle));
+    }
+
+    #[test]
+    fn watch_property() {
+        let mut cam = make_dummy();
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let rx = cam.watch_property(ctrl).unwrap();
+        let gain = cam
+            .watch_property(GenCamCtrl::Analog(AnalogCtrl::Gain))
+            .unwrap();
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        cam.set_exposure(Duration::from_millis(20)).unwrap();
+        assert_eq!(rx.try_recv(), Ok(Duration::from_millis(10).into()));
+        assert_eq!(rx.try_recv(), Ok(Duration::from_millis(20).into()));
+        assert!(rx.try_recv().is_err());
+        assert!(gain.try_recv().is_err());
+        assert!(matches!(
+            cam.watch_property(GenCamCtrl::Sensor(SensorCtrl::ReverseX)),
+            Err(GenCamError::PropertyError {
+                error: PropertyError::NotFound,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn stream() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::SolidValue(7));
+        cam.set_exposure(Duration::from_millis(5)).unwrap();
+        let rx = cam.start_stream().unwrap();
+        assert_eq!(
+            cam.start_stream().unwrap_err(),
+            GenCamError::ExposureInProgress
+        );
+        assert!(cam.capture().is_err());
+        for _ in 0..5 {
+            let (_, img) = rx.recv_timeout(Duration::from_secs(1)).unwrap().unwrap();
+            assert_eq!((img.width(), img.height()), (1920, 1080));
+        }
+        cam.stop_stream().unwrap();
+        assert_eq!(cam.stop_stream(), Err(GenCamError::ExposureNotStarted));
+        assert!(!cam.is_capturing());
+        // queued frames are still delivered, then the stream ends
+        while let Ok(frame) = rx.recv_timeout(Duration::from_secs(1)) {
+            assert!(matches!(frame, Ok(_) | Err(GenCamError::Cancelled)));
+        }
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        assert!(cam.capture().is_ok());
+    }
+
+    #[test]
+    fn frame_meta() {
+        let mut cam = GenCamDummy::with_seed(42);
+        assert_eq!(cam.last_frame_meta(), Ok(None));
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.capture().unwrap();
+        let first = cam.last_frame_meta().unwrap().unwrap();
+        assert_eq!(first.frame_id, 0);
+        assert_eq!(first.hardware_timestamp, None);
+
+        cam.set_exposure(Duration::from_millis(5)).unwrap();
+        let rx = cam.start_stream().unwrap();
+        let mut metas = vec![rx.recv_timeout(Duration::from_secs(1)).unwrap().unwrap().0];
+        cam.stop_stream().unwrap();
+        // the remaining frames, up to the frame cancelled by stopping the stream
+        metas.extend(
+            rx.iter()
+                .filter_map(|frame| frame.ok().map(|(meta, _)| meta)),
+        );
+        assert!(metas[0].frame_id > first.frame_id);
+        assert!(metas.windows(2).all(|w| {
+            w[0].frame_id < w[1].frame_id && w[0].host_timestamp <= w[1].host_timestamp
+        }));
+        // every frame is received or dropped, except one finished as the stream stopped
+        let produced = cam.last_frame_meta().unwrap().unwrap().frame_id - first.frame_id;
+        let sent = metas.len() as u64 + rx.dropped();
+        assert!(produced == sent || produced == sent + 1);
+    }
+
+    #[test]
+    fn capture_with_timeout() {
+        let mut cam = make_dummy();
+        cam.set_exposure(Duration::from_secs(1)).unwrap();
+        assert_eq!(
+            cam.capture_with_timeout(Duration::from_millis(50))
+                .unwrap_err(),
+            GenCamError::TimedOut
+        );
+        assert_eq!(cam.camera_state(), Ok(GenCamState::Idle));
+        assert!(!cam.is_capturing());
+
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        assert!(cam.capture_with_timeout(Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn exposure_remaining() {
+        let mut cam = make_dummy();
+        assert_eq!(cam.exposure_remaining(), Ok(None));
+        cam.set_exposure(Duration::from_secs(1)).unwrap();
+        cam.start_exposure().unwrap();
+        let first = cam.exposure_remaining().unwrap().unwrap();
+        assert!(first <= Duration::from_secs(1));
+        std::thread::sleep(Duration::from_millis(100));
+        let second = cam.exposure_remaining().unwrap().unwrap();
+        assert!(second < first);
+        assert!(second <= Duration::from_millis(900));
+        cam.cancel_capture().unwrap();
+        assert_eq!(cam.exposure_remaining(), Ok(None));
+    }
+
+    #[test]
+    fn set_roi() {
+        let mut cam = make_dummy();
+        let roi = GenCamRoi {
+            x_min: 100,
+            y_min: 50,
+            width: 640,
+            height: 480,
+        };
+        assert_eq!(cam.set_roi(&roi), Ok(&roi));
+        assert!(matches!(
+            cam.set_roi(&GenCamRoi { x_min: 1281, ..roi }),
+            Err(GenCamError::OutOfBounds(_))
+        ));
+        assert_eq!(cam.get_roi(), &roi);
+    }
+
+    #[test]
+    fn roi_follows_sensor_size() {
+        let mut cam = GenCamDummy::with_seed(42);
+        let roi = GenCamRoi {
+            x_min: 100,
+            y_min: 50,
+            width: 640,
+            height: 480,
+        };
+        let mut vals = cam.vals.lock().unwrap();
+        vals.insert(
+            GenCamCtrl::Sensor(SensorCtrl::WidthMax),
+            (PropertyValue::Unsigned(640), false),
+        );
+        vals.insert(
+            GenCamCtrl::Sensor(SensorCtrl::HeightMax),
+            (PropertyValue::Unsigned(480), false),
+        );
+        drop(vals);
+        assert!(matches!(
+            cam.set_roi(&roi),
+            Err(GenCamError::OutOfBounds(_))
+        ));
+        cam.reset().unwrap();
+        assert_eq!(
+            cam.get_roi(),
+            &GenCamRoi {
+                x_min: 0,
+                y_min: 0,
+                width: 640,
+                height: 480,
+            }
+        );
+    }
+
+    #[test]
+    fn sensor_size() {
+        let mut cam = make_dummy();
+        assert_eq!(cam.sensor_size(), Ok((1920, 1080)));
+        // the sensor size is read-only
+        assert_eq!(
+            cam.set_property(
+                GenCamCtrl::Sensor(SensorCtrl::WidthMax),
+                &PropertyValue::Unsigned(640)
+            ),
+            Err(GenCamError::PropertyError {
+                control: GenCamCtrl::Sensor(SensorCtrl::WidthMax),
+                error: PropertyError::ReadOnly,
+            })
+        );
+        assert_eq!(cam.sensor_size(), Ok((1920, 1080)));
+    }
+
+    /// Make a dummy camera with binning controls described by `lims`.
+    fn make_binning_dummy(lims: PropertyLims) -> GenCamDummy {
+        let mut cam = GenCamDummy::new(&GenCamDriverDummy::default().list_devices().unwrap()[0]);
+        for ctrl in [SensorCtrl::BinningHorz, SensorCtrl::BinningVert] {
+            cam.caps.insert(
+                GenCamCtrl::Sensor(ctrl),
+                Property::new(lims.clone(), false, false),
+            );
+            cam.vals.lock().unwrap().insert(
+                GenCamCtrl::Sensor(ctrl),
+                (PropertyValue::Unsigned(1), false),
+            );
+        }
+        cam
+    }
+
+    #[test]
+    fn binning_unsigned() {
+        let mut cam = make_binning_dummy(PropertyLims::Unsigned {
+            min: 1,
+            max: 4,
+            step: 1,
+            default: 1,
+        });
+        assert_eq!(cam.get_binning(), Ok((1, 1)));
+        cam.set_binning(2, 3).unwrap();
+        assert_eq!(cam.get_binning(), Ok((2, 3)));
+        assert_eq!(
+            cam.get_roi(),
+            &GenCamRoi {
+                x_min: 0,
+                y_min: 0,
+                width: 960,
+                height: 360,
+            }
+        );
+        assert!(matches!(
+            cam.set_binning(8, 1),
+            Err(GenCamError::PropertyError {
+                error: PropertyError::ValueOutOfRange { .. },
+                ..
+            })
+        ));
+        assert!(matches!(
+            cam.set_binning(0, 1),
+            Err(GenCamError::PropertyError {
+                error: PropertyError::ValueNotSupported,
+                ..
+            })
+        ));
+        assert_eq!(cam.get_binning(), Ok((2, 3)));
+    }
+
+    #[test]
+    fn binning_enum_unsigned() {
+        let mut cam = make_binning_dummy(PropertyLims::EnumUnsigned {
+            variants: vec![1, 2, 4],
+            default: 1,
+        });
+        cam.set_binning(4, 4).unwrap();
+        assert_eq!(cam.get_binning(), Ok((4, 4)));
+        assert_eq!(
+            cam.set_binning(2, 3),
+            Err(GenCamError::PropertyError {
+                control: GenCamCtrl::Sensor(SensorCtrl::BinningVert),
+                error: PropertyError::ValueNotSupported,
+            })
+        );
+        assert_eq!(cam.get_binning(), Ok((4, 4)));
+    }
+
+    #[test]
+    fn descriptor_info() {
+        let desc = dummy_descriptor();
+        assert_eq!(desc.get_str("Interface"), Some("Aether"));
+        assert_eq!(desc.get_bool("Interface"), None);
+        assert_eq!(desc.get_str("Serial"), None);
+        assert_eq!(desc.get_u64("Serial"), None);
+    }
+
+    #[test]
+    fn connect_by_name() {
+        let mut driver = GenCamDriverDummy::default();
+        let cam = driver.connect_by_name("Dummy Camera").unwrap();
+        assert_eq!(cam.camera_name(), "Dummy Camera");
+        assert!(matches!(
+            driver.connect_by_name("Dummy"),
+            Err(GenCamError::NoCamerasAvailable)
+        ));
+        // the dummy camera has no serial number
+        assert!(matches!(
+            driver.connect_by_serial("DEADBEEF"),
+            Err(GenCamError::NoCamerasAvailable)
+        ));
+    }
+
+    #[test]
+    fn hot_plug() {
+        let mut driver = GenCamDriverDummy::default();
+        let changes = driver.poll_changes().unwrap();
+        assert_eq!(changes.added, vec![dummy_descriptor()]);
+        assert!(changes.removed.is_empty());
+        assert!(driver.poll_changes().unwrap().is_empty());
+
+        driver.set_plugged(false);
+        assert_eq!(driver.available_devices(), 0);
+        let changes = driver.poll_changes().unwrap();
+        assert_eq!(changes.removed, vec![dummy_descriptor()]);
+        assert!(changes.added.is_empty());
+        assert!(matches!(
+            driver.connect_first_device(),
+            Err(GenCamError::NoCamerasAvailable)
+        ));
+
+        driver.set_plugged(true);
+        // listing the devices updates the cache too
+        driver.list_devices().unwrap();
+        assert!(driver.poll_changes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn config_roundtrip() {
+        let mut cam = make_dummy();
+        let config = cam.export_config().unwrap();
+        assert_eq!(config.values.len(), cam.list_properties().len());
+        let json = serde_json::to_string(&config).unwrap();
+        let config: CameraConfig = serde_json::from_str(&json).unwrap();
+
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        // read-only controls are skipped, so nothing fails
+        assert_eq!(cam.import_config(&config), Ok(vec![]));
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_secs(1)));
+
+        let mut config = config;
+        let temp = GenCamCtrl::Device(DeviceCtrl::Temperature);
+        config
+            .values
+            .insert(temp, (PropertyValue::Float(-10.0), false));
+        assert_eq!(
+            cam.import_config(&config),
+            Ok(vec![(
+                temp,
+                GenCamError::PropertyError {
+                    control: temp,
+                    error: PropertyError::NotFound
+                }
+            )])
+        );
+    }
+
+    #[test]
+    fn select_pixel_format() {
+        use GenCamPixelBpp::*;
+        let mut cam = make_dummy();
+        assert_eq!(cam.select_pixel_format(&[Bpp12, Bpp8]), Ok(Bpp8));
+        assert_eq!(
+            cam.get_property(GenCamCtrl::Sensor(SensorCtrl::PixelFormat)),
+            Ok((PropertyValue::PixelFmt(Bpp8), false))
+        );
+        // none of the preferred formats are available
+        assert_eq!(cam.select_pixel_format(&[Bpp16, Bpp12]), Ok(Bpp24));
+        assert_eq!(cam.select_pixel_format(&[]), Ok(Bpp24));
+
+        let mut cam = GenCamDummy::new(&dummy_descriptor());
+        cam.caps
+            .remove(&GenCamCtrl::Sensor(SensorCtrl::PixelFormat));
+        assert!(matches!(
+            cam.select_pixel_format(&[Bpp8]),
+            Err(GenCamError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn inserted_metadata_keys() {
+        let mut cam = make_dummy();
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.set_roi(&GenCamRoi {
+            x_min: 10,
+            y_min: 20,
+            width: 100,
+            height: 100,
+        })
+        .unwrap();
+        let img = cam.capture().unwrap();
+        let offset = |key| img.get_key(key).map(|item| item.get_value().clone());
+        assert_eq!(offset(metadata_keys::X_OFFSET), Some(GenericValue::U32(10)));
+        assert_eq!(offset(metadata_keys::Y_OFFSET), Some(GenericValue::U32(20)));
+    }
+
+    #[test]
+    fn capture_with_metadata() {
+        let mut cam = make_dummy();
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        let img = cam.capture_with_metadata().unwrap();
+        let exptime = img.get_key(metadata_keys::EXPOSURE_TIME).unwrap();
+        assert_eq!(exptime.get_value(), &GenericValue::F64(0.01));
+        assert!(img.get_key(metadata_keys::GAIN).is_some());
+        assert!(img.get_key(metadata_keys::INSTRUMENT).is_some());
+        // the dummy does not report its temperature or binning
+        assert!(img.get_key(metadata_keys::CCD_TEMP).is_none());
+        assert!(img.get_key(metadata_keys::BINNING_X).is_none());
+    }
+
+    #[test]
+    fn capture_oriented_software_flip() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::Gradient);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        let reverse = GenCamCtrl::Sensor(SensorCtrl::ReverseX);
+        assert!(!cam.list_properties().contains_key(&reverse));
+        cam.capture_oriented(true, false).unwrap();
+        assert_eq!(&cam.data[..3], &[255, 255, 255]);
+        assert_eq!(&cam.data[1919 * 3..1920 * 3], &[0, 0, 0]);
+        // a vertical flip leaves a horizontal gradient unchanged
+        cam.capture_oriented(false, true).unwrap();
+        assert_eq!(&cam.data[..3], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn info_handle_disconnect() {
+        let cam = make_dummy();
+        let info = cam.info_handle().unwrap();
+        assert!(info.is_connected());
+        assert!(info.camera_ready());
+        drop(cam);
+        assert!(!info.is_connected());
+        assert!(!info.camera_ready());
+    }
+
+    #[test]
+    fn info_handle_shares_state() {
+        let mut cam = make_dummy();
+        let mut info = cam.info_handle().unwrap();
+        assert_eq!(info.camera_name(), cam.camera_name());
+        assert_eq!(info.list_properties(), cam.list_properties());
+
+        // property values agree in both directions
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        assert_eq!(info.get_property(ctrl), cam.get_property(ctrl));
+        info.set_property(ctrl, &Duration::from_secs(2).into())
+            .unwrap();
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_secs(2)));
+
+        assert!(!info.is_capturing());
+        cam.start_exposure().unwrap();
+        assert!(info.is_capturing());
+        assert!(matches!(info.camera_state(), Ok(GenCamState::Exposing(_))));
+        info.cancel_capture().unwrap();
+        assert!(!cam.is_capturing());
+        assert_eq!(cam.camera_state(), Ok(GenCamState::Idle));
+    }
+
+    #[test]
+    fn cancel_unblocks_capture() {
+        let mut cam = GenCamDummy::new(&dummy_descriptor());
+        cam.set_exposure(Duration::from_secs(10)).unwrap();
+        let info = cam.info_handle().unwrap();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            info.cancel_capture()
+        });
+        let start = Instant::now();
+        assert_eq!(cam.capture().unwrap_err(), GenCamError::Cancelled);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(canceller.join().unwrap(), Ok(()));
+        assert_eq!(cam.camera_state(), Ok(GenCamState::Idle));
+    }
+
+    #[test]
+    fn cancelled_capture() {
+        let mut cam = make_dummy();
+        cam.set_exposure(Duration::from_secs(10)).unwrap();
+        cam.start_exposure().unwrap();
+        cam.cancel_capture().unwrap();
+        assert!(!cam.is_capturing());
+        assert_eq!(cam.camera_state(), Ok(GenCamState::Idle));
+        assert!(matches!(
+            cam.poll_exposure(),
+            PollExposure::Ready(Err(GenCamError::Cancelled))
+        ));
+        // the cancellation is only reported once
+        assert!(matches!(
+            cam.poll_exposure(),
+            PollExposure::Ready(Err(GenCamError::ExposureNotStarted))
+        ));
+        assert_eq!(cam.cancel_capture(), Err(GenCamError::ExposureNotStarted));
+    }
+
+    #[test]
+    fn snapshot_properties() {
+        let cam = GenCamDummy::new(&dummy_descriptor());
+        let gain = GenCamCtrl::Analog(AnalogCtrl::Gain);
+        cam.vals.lock().unwrap().remove(&gain);
+        let snapshot = cam.snapshot_properties().unwrap();
+        assert_eq!(snapshot.len(), cam.list_properties().len());
+        let (prop, value) = &snapshot[&GenCamCtrl::Exposure(ExposureCtrl::ExposureTime)];
+        assert_eq!(prop.get_min(), Ok(Duration::from_millis(1).into()));
+        assert_eq!(prop.get_max(), Ok(Duration::from_secs(60).into()));
+        assert_eq!(value, &Some((Duration::from_secs(1).into(), false)));
+        // the definition is kept even if the value can not be read
+        assert_eq!(snapshot[&gain].1, None);
+    }
+
+    #[test]
+    fn temperature() {
+        let cam = GenCamDummy::new(&dummy_descriptor());
+        assert_eq!(
+            cam.temperature(),
+            Err(GenCamError::PropertyError {
+                control: GenCamCtrl::Device(DeviceCtrl::Temperature),
+                error: PropertyError::NotFound,
+            })
+        );
+        cam.vals.lock().unwrap().insert(
+            GenCamCtrl::Device(DeviceCtrl::Temperature),
+            (PropertyValue::Float(-10.0), false),
+        );
+        assert_eq!(cam.temperature(), Ok(-10.0));
+    }
+
+    #[test]
+    fn cooler_status() {
+        let cam = GenCamDummy::new(&dummy_descriptor());
+        assert!(matches!(
+            cam.cooler_status(),
+            Err(GenCamError::PropertyError {
+                error: PropertyError::NotFound,
+                ..
+            })
+        ));
+        cam.vals.lock().unwrap().insert(
+            GenCamCtrl::Device(DeviceCtrl::Temperature),
+            (PropertyValue::Float(-9.7), false),
+        );
+        assert_eq!(
+            cam.cooler_status(),
+            Ok(CoolerStatus {
+                temperature: -9.7,
+                setpoint: None,
+                power_percent: None,
+                at_setpoint: false,
+            })
+        );
+        let mut vals = cam.vals.lock().unwrap();
+        vals.insert(
+            GenCamCtrl::Device(DeviceCtrl::CoolerTemp),
+            (PropertyValue::Float(-10.0), false),
+        );
+        vals.insert(
+            GenCamCtrl::Device(DeviceCtrl::CoolerPower),
+            (PropertyValue::Int(45), false),
+        );
+        drop(vals);
+        assert_eq!(
+            cam.cooler_status(),
+            Ok(CoolerStatus {
+                temperature: -9.7,
+                setpoint: Some(-10.0),
+                power_percent: Some(45.0),
+                at_setpoint: true,
+            })
+        );
+    }
+
+    #[test]
+    fn frame_rate() {
+        let mut cam = GenCamDummy::new(&dummy_descriptor());
+        let ctrl = GenCamCtrl::FrameTime(FrameTimeCtrl::FrameTime);
+        cam.caps.insert(
+            ctrl,
+            Property::new(
+                PropertyLims::Duration {
+                    min: Duration::from_millis(1),
+                    max: Duration::from_secs(1),
+                    step: Duration::from_micros(10),
+                    default: Duration::from_millis(100),
+                },
+                true,
+                false,
+            ),
+        );
+        cam.vals
+            .lock()
+            .unwrap()
+            .insert(ctrl, (Duration::from_millis(100).into(), false));
+        assert_eq!(cam.get_frame_rate(), Ok(10.0));
+        cam.set_frame_rate(30.0).unwrap();
+        // 33.333.. ms snapped to the 10 us step
+        assert_eq!(
+            Duration::try_from(cam.get_property(ctrl).unwrap().0).unwrap(),
+            Duration::from_micros(33330)
+        );
+        assert!((cam.get_frame_rate().unwrap() - 30.0).abs() < 0.01);
+        // out of range frame rates are clamped
+        cam.set_frame_rate(0.5).unwrap();
+        assert_eq!(cam.get_frame_rate(), Ok(1.0));
+        for fps in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                cam.set_frame_rate(fps),
+                Err(GenCamError::InvalidValue(_))
+            ));
+        }
+        cam.vals
+            .lock()
+            .unwrap()
+            .insert(ctrl, (Duration::ZERO.into(), false));
+        assert!(matches!(
+            cam.get_frame_rate(),
+            Err(GenCamError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn batch_properties() {
+        let mut cam = make_dummy();
+        let exposure = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let gain = GenCamCtrl::Analog(AnalogCtrl::Gain);
+        let invalid = GenCamCtrl::Device(DeviceCtrl::CoolerTemp);
+        let res = cam
+            .set_properties(&[
+                (exposure, Duration::from_millis(20).into(), false),
+                (invalid, PropertyValue::Float(-10.0), false),
+                (gain, PropertyValue::Float(5.0), true),
+            ])
+            .unwrap();
+        assert_eq!(res.len(), 3);
+        assert!(res[0].is_ok() && res[2].is_ok());
+        assert!(matches!(
+            res[1],
+            Err(GenCamError::PropertyError {
+                error: PropertyError::NotFound,
+                ..
+            })
+        ));
+        let res = cam.get_properties(&[exposure, invalid, gain]);
+        assert_eq!(res[0], Ok((Duration::from_millis(20).into(), false)));
+        assert!(res[1].is_err());
+        assert_eq!(res[2], Ok((PropertyValue::Float(5.0), true)));
+    }
+
+    #[test]
+    fn exposure_and_gain() {
+        let mut cam = make_dummy();
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_secs(1)));
+        cam.set_exposure(Duration::from_millis(250)).unwrap();
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_millis(250)));
+        assert_eq!(cam.get_gain(), Ok(0.0));
+        cam.set_gain(12.5).unwrap();
+        assert_eq!(cam.get_gain(), Ok(12.5));
+        // type mismatch is reported as a property error
+        cam.set_property(GenCamCtrl::Analog(AnalogCtrl::Gain), &1u64.into())
+            .unwrap();
+        assert!(matches!(
+            cam.get_gain(),
+            Err(GenCamError::PropertyError {
+                error: PropertyError::InvalidControlType { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn sequence_captures_all_frames() {
+        let mut cam = make_dummy();
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        let frames = CaptureSequence::new(3).run(cam.as_mut()).unwrap();
+        assert_eq!(frames.len(), 3);
+    }
+
+    #[test]
+    fn sequence_cancel_returns_partial() {
+        let desc = GenCamDriverDummy::default()
+            .list_devices()
+            .unwrap()
+            .remove(0);
+        let mut cam = GenCamDummy::new(&desc);
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        let rx = cam.subscribe_state().unwrap();
+        let (state, vals) = (cam.capture_state.clone(), cam.vals.clone());
+        let canceller = std::thread::spawn(move || {
+            let mut exposures = rx.iter().filter(GenCamState::is_exposing);
+            // the second frame has read its exposure time, so the third one never
+            // finishes on its own, and is cancelled while exposing
+            exposures.nth(1).unwrap();
+            vals.lock().unwrap().insert(
+                GenCamCtrl::Exposure(ExposureCtrl::ExposureTime),
+                (Duration::from_secs(3600).into(), false),
+            );
+            exposures.next().unwrap();
+            state.cancel_capture().unwrap();
+        });
+        let err = CaptureSequence::new(5).run(&mut cam).unwrap_err();
+        canceller.join().unwrap();
+        assert_eq!(err.frames.len(), 2);
+        assert_eq!(err.error, GenCamError::Cancelled);
+    }
+
+    #[test]
+    fn bracket_exposures() {
+        let (start, stop) = (Duration::from_millis(1), Duration::from_millis(100));
+        assert_eq!(
+            crate::bracket_exposures(start, stop, 3, BracketSpacing::Logarithmic),
+            Ok(vec![start, Duration::from_millis(10), stop])
+        );
+        let linear = crate::bracket_exposures(start, stop, 3, BracketSpacing::Linear).unwrap();
+        assert_eq!(linear[1].as_micros(), 50_500);
+        for (start, stop, steps) in [(start, stop, 1), (stop, start, 3)] {
+            assert!(matches!(
+                crate::bracket_exposures(start, stop, steps, BracketSpacing::Linear),
+                Err(GenCamError::InvalidValue(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn frame_histogram() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::SolidValue(7));
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        let img = cam.capture().unwrap();
+        let pixels = (img.width() * img.height()) as u64;
+        let hist = crate::frame_histogram(&img, 256);
+        assert_eq!(hist.len(), 256);
+        assert_eq!(hist[7], pixels * 3);
+        assert_eq!(hist.iter().filter(|&&count| count > 0).count(), 1);
+        // 7 / 256 * 16 rounds down to the first bin
+        assert_eq!(crate::frame_histogram(&img, 16)[0], pixels * 3);
+        let channels = crate::frame_histogram_per_channel(&img, 256);
+        assert_eq!(channels.len(), 3);
+        assert!(channels.iter().all(|hist| hist[7] == pixels));
+    }
+
+    #[test]
+    fn frame_stats() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.set_pattern(DummyPattern::Gradient);
+        let stats = crate::frame_stats(&cam.capture().unwrap()).unwrap();
+        // every row and channel is the same ramp from 0 to 255
+        let ramp: Vec<f64> = (0..1920).map(|x| (x * 255 / 1919) as f64).collect();
+        let mean = ramp.iter().sum::<f64>() / 1920.0;
+        let var = ramp.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / 1920.0;
+        assert!((stats.mean - mean).abs() < 1e-9);
+        assert!((stats.stddev - var.sqrt()).abs() < 1e-6);
+        assert_eq!((stats.min, stats.max), (0.0, 255.0));
+        assert!((stats.saturated_fraction - 1.0 / 1920.0).abs() < 1e-12);
+
+        cam.set_pattern(DummyPattern::SolidValue(255));
+        let stats = crate::frame_stats(&cam.capture().unwrap()).unwrap();
+        assert_eq!(stats.mean, 255.0);
+        assert_eq!(stats.stddev, 0.0);
+        assert_eq!(stats.saturated_fraction, 1.0);
+    }
+
+    #[test]
+    fn capture_checked() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        cam.set_pattern(DummyPattern::SolidValue(255));
+        assert_eq!(
+            cam.capture_checked(0.5).unwrap_err(),
+            GenCamError::ExposureFailed("saturated".into())
+        );
+        assert!(cam.capture_checked(1.0).is_ok());
+        // the last column of the gradient is saturated
+        cam.set_pattern(DummyPattern::Gradient);
+        assert!(cam.capture_checked(0.01).is_ok());
+        assert!(cam.capture_checked(0.0).is_err());
+        assert!(matches!(
+            cam.capture_checked(1.5),
+            Err(GenCamError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn peek_buffer() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        assert_eq!(cam.peek_buffer(), Err(GenCamError::ExposureNotStarted));
+        let mut buf = Vec::new();
+        cam.download_into(&mut buf).unwrap();
+        assert_eq!(cam.peek_buffer(), Ok(buf.as_slice()));
+
+        let img = cam.capture().unwrap();
+        let DynamicImageRef::U8(data) = img.get_image() else {
+            panic!("expected an 8 bit image");
+        };
+        let bytes = data.as_slice().to_vec();
+        assert_eq!(cam.peek_buffer(), Ok(bytes.as_slice()));
+    }
+
+    #[test]
+    fn download_into() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::Gradient);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        let mut buf = Vec::new();
+        let meta = cam.download_into(&mut buf).unwrap();
+        assert_eq!((meta.width, meta.height), (1920, 1080));
+        assert_eq!(meta.bpp, GenCamPixelBpp::Bpp8);
+        assert_eq!(meta.color_space, refimage::ColorSpace::Rgb);
+        assert_eq!(
+            crate::validate_buffer_len(
+                buf.len(),
+                meta.width,
+                meta.height,
+                &meta.color_space,
+                meta.bpp
+            ),
+            Ok(())
+        );
+        assert_eq!((buf[0], buf[1919 * 3]), (0, 255));
+
+        // the second frame of the same size reuses the allocation
+        let (ptr, capacity) = (buf.as_ptr(), buf.capacity());
+        assert_eq!(cam.download_into(&mut buf), Ok(meta));
+        assert_eq!((buf.as_ptr(), buf.capacity()), (ptr, capacity));
+    }
+
+    #[test]
+    fn properties_sorted() {
+        let cam = make_dummy();
+        let props = cam.properties_sorted();
+        assert_eq!(props.len(), cam.list_properties().len());
+        let ctrls: Vec<_> = props.iter().map(|(ctrl, _)| *ctrl).collect();
+        assert_eq!(
+            cam.properties_sorted()
+                .iter()
+                .map(|(ctrl, _)| *ctrl)
+                .collect::<Vec<_>>(),
+            ctrls
+        );
+        assert!(ctrls.windows(2).all(|w| w[0].stable_cmp(&w[1]).is_lt()));
+        let all = GenCamCtrl::all_known();
+        let known: Vec<_> = all.iter().filter(|ctrl| ctrls.contains(ctrl)).collect();
+        assert_eq!(ctrls.iter().collect::<Vec<_>>(), known);
+    }
+
+    #[test]
+    fn exposure_limits() {
+        let cam = make_dummy();
+        assert_eq!(
+            cam.exposure_limits(),
+            Ok((Duration::from_millis(1), Duration::from_secs(60)))
+        );
+    }
+
+    #[test]
+    fn exposure_duration() {
+        let mut cam = make_dummy();
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        // the dummy reports a duration, so the unit does not matter
+        cam.set_exposure(Duration::from_micros(1500)).unwrap();
+        assert!(matches!(
+            cam.get_property(ctrl),
+            Ok((PropertyValue::Duration(_), false))
+        ));
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_micros(1500)));
+    }
+
+    #[test]
+    fn set_gain_db() {
+        let mut cam = make_dummy();
+        // the gain of the dummy starts at zero, which can not be a dB reference
+        assert!(matches!(
+            cam.set_gain_db(6.0),
+            Err(GenCamError::InvalidValue(_))
+        ));
+        assert_eq!(cam.get_gain(), Ok(0.0));
+    }
+
+    #[test]
+    fn auto_expose() {
+        // the mean of the gradient does not depend on the exposure time
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::Gradient);
+        cam.set_exposure(Duration::from_millis(5)).unwrap();
+        assert_eq!(
+            crate::auto_expose(&mut cam, 127.5, 1.0, 5),
+            Ok(Duration::from_millis(5))
+        );
+        // twice as bright, doubling each iteration until out of iterations
+        assert_eq!(
+            crate::auto_expose(&mut cam, 255.0, 1.0, 3),
+            Ok(Duration::from_millis(40))
+        );
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_millis(40)));
+        // clamped to the minimum exposure time
+        cam.set_exposure(Duration::from_millis(2)).unwrap();
+        assert_eq!(
+            crate::auto_expose(&mut cam, 12.75, 1.0, 5),
+            Ok(Duration::from_millis(1))
+        );
+        for (target, tolerance) in [(f64::NAN, 1.0), (f64::INFINITY, 1.0), (127.5, -1.0)] {
+            assert!(matches!(
+                crate::auto_expose(&mut cam, target, tolerance, 5),
+                Err(GenCamError::InvalidValue(_))
+            ));
+        }
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn bracket_restores_exposure() {
+        let mut cam = make_dummy();
+        cam.set_exposure(Duration::from_millis(20)).unwrap();
+        let frames = crate::bracket(
+            cam.as_mut(),
+            Duration::from_millis(1),
+            Duration::from_millis(9),
+            3,
+            BracketSpacing::Logarithmic,
+        )
+        .unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_millis(20)));
+
+        // restored on error as well
+        let mut cam = GenCamDummy::new(&dummy_descriptor());
+        cam.set_exposure(Duration::from_millis(20)).unwrap();
+        let state = cam.capture_state.clone();
+        let canceller = std::thread::spawn(move || {
+            while state.cancel_capture().is_err() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        });
+        let res = crate::bracket(
+            &mut cam,
+            Duration::from_millis(50),
+            Duration::from_millis(100),
+            2,
+            BracketSpacing::Linear,
+        );
+        canceller.join().unwrap();
+        assert_eq!(res.unwrap_err(), GenCamError::Cancelled);
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_millis(20)));
     }
 }
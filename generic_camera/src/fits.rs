@@ -0,0 +1,312 @@
+/*!
+ * # FITS Export
+ * This module writes captured frames to FITS files, with the capture settings stored in
+ * [`GenericImageRef`] metadata (see [`metadata_keys`]) copied to the FITS header.
+ *
+ * The frame is written as the primary HDU. Multi-channel frames are written as a cube
+ * with one plane per channel (`NAXIS3`), since FITS does not support interleaved pixels.
+ */
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use refimage::{DynamicImageRef, GenericImageRef, GenericValue, ImageProps};
+
+use crate::metadata_keys;
+use crate::{GenCamError, GenCamResult};
+
+/// Size of a FITS block, in bytes.
+const BLOCK_LEN: usize = 2880;
+/// Size of a FITS header card, in bytes.
+const CARD_LEN: usize = 80;
+/// Maximum length of a string value on a header card, excluding the quotes.
+const STRING_LEN: usize = CARD_LEN - 12;
+
+/// The metadata keys copied to the FITS header, if present on the image.
+const HEADER_KEYS: [&str; 8] = [
+    metadata_keys::EXPOSURE_TIME,
+    metadata_keys::GAIN,
+    metadata_keys::CCD_TEMP,
+    metadata_keys::BINNING_X,
+    metadata_keys::BINNING_Y,
+    metadata_keys::X_OFFSET,
+    metadata_keys::Y_OFFSET,
+    metadata_keys::INSTRUMENT,
+];
+
+/// Save a captured frame to a FITS file at `path`.
+///
+/// - 8-bit frames are written with `BITPIX = 8`.
+/// - 16-bit frames are written with `BITPIX = 16`, using the standard `BZERO = 32768`,
+///   `BSCALE = 1` convention for unsigned data.
+/// - Floating point frames are written with `BITPIX = -32`.
+///
+/// The metadata keys in [`metadata_keys`] that are present on the image are copied to
+/// the header, along with their comments. Other metadata is not written. Strings are
+/// truncated to fit on a header card, and non-finite floating point values are skipped.
+///
+/// # Errors
+/// - [`GenCamError::InvalidPath`] if the file can not be written.
+pub fn save_fits(img: &GenericImageRef<'_>, path: &Path) -> GenCamResult<()> {
+    let io_err = |e: std::io::Error| GenCamError::InvalidPath(format!("{}: {e}", path.display()));
+    let mut out = BufWriter::new(File::create(path).map_err(io_err)?);
+    out.write_all(&fits_bytes(img)).map_err(io_err)?;
+    out.flush().map_err(io_err)
+}
+
+/// Encode a frame as a FITS file.
+fn fits_bytes(img: &GenericImageRef<'_>) -> Vec<u8> {
+    let (width, height) = (img.width(), img.height());
+    let pixels = (width * height).max(1);
+    let (bitpix, channels, data) = match img.get_image() {
+        DynamicImageRef::U8(data) => {
+            let data = data.as_slice();
+            let channels = data.len() / pixels;
+            (8, channels, planar(data, channels, |v, out| out.push(*v)))
+        }
+        DynamicImageRef::U16(data) => {
+            let data = data.as_slice();
+            let channels = data.len() / pixels;
+            let data = planar(data, channels, |v, out| {
+                out.extend_from_slice(&((*v as i32 - 32768) as i16).to_be_bytes())
+            });
+            (16, channels, data)
+        }
+        DynamicImageRef::F32(data) => {
+            let data = data.as_slice();
+            let channels = data.len() / pixels;
+            let data = planar(data, channels, |v, out| {
+                out.extend_from_slice(&v.to_be_bytes())
+            });
+            (-32, channels, data)
+        }
+    };
+
+    let mut header = Vec::new();
+    header.push(card("SIMPLE", "T", Some("conforms to FITS standard")));
+    header.push(card("BITPIX", &bitpix.to_string(), None));
+    header.push(card("NAXIS", if channels > 1 { "3" } else { "2" }, None));
+    header.push(card("NAXIS1", &width.to_string(), None));
+    header.push(card("NAXIS2", &height.to_string(), None));
+    if channels > 1 {
+        header.push(card("NAXIS3", &channels.to_string(), None));
+    }
+    if bitpix == 16 {
+        header.push(card("BZERO", "32768", Some("offset for unsigned data")));
+        header.push(card("BSCALE", "1", None));
+    }
+    for key in HEADER_KEYS {
+        let Some(item) = img.get_key(key) else {
+            continue;
+        };
+        if let Some(value) = card_value(item.get_value()) {
+            header.push(card(key, &value, item.get_comment()));
+        }
+    }
+    header.push(format!("{:<CARD_LEN$}", "END"));
+
+    let mut out: Vec<u8> = header.concat().into_bytes();
+    pad_block(&mut out, b' ');
+    out.extend_from_slice(&data);
+    pad_block(&mut out, 0);
+    out
+}
+
+/// Convert interleaved pixels to planes in big-endian byte order using `encode`.
+fn planar<T>(data: &[T], channels: usize, encode: impl Fn(&T, &mut Vec<u8>)) -> Vec<u8> {
+    let mut out = Vec::with_capacity(std::mem::size_of_val(data));
+    for channel in 0..channels.max(1) {
+        data.iter()
+            .skip(channel)
+            .step_by(channels.max(1))
+            .for_each(|v| encode(v, &mut out));
+    }
+    out
+}
+
+/// Format a header card with a value already formatted for FITS, see [`card_value`].
+///
+/// The comment is truncated to the space left on the card, or dropped if there is none.
+fn card(key: &str, value: &str, comment: Option<&str>) -> String {
+    let mut card = if value.starts_with('\'') {
+        format!("{key:<8}= {value}")
+    } else {
+        format!("{key:<8}= {value:>20}")
+    };
+    if let Some(comment) = comment.filter(|_| card.len() + 3 < CARD_LEN) {
+        card.push_str(" / ");
+        let room = CARD_LEN - card.len();
+        card.extend(comment.chars().filter(char::is_ascii).take(room));
+    }
+    card.extend(std::iter::repeat_n(' ', CARD_LEN - card.len()));
+    card
+}
+
+/// Format a metadata value for a FITS header card, or `None` if it is not supported.
+fn card_value(value: &GenericValue) -> Option<String> {
+    Some(match value {
+        GenericValue::U8(v) => v.to_string(),
+        GenericValue::U16(v) => v.to_string(),
+        GenericValue::U32(v) => v.to_string(),
+        GenericValue::U64(v) => v.to_string(),
+        GenericValue::I16(v) => v.to_string(),
+        GenericValue::I32(v) => v.to_string(),
+        GenericValue::I64(v) => v.to_string(),
+        GenericValue::F32(v) => fmt_float(*v as f64)?,
+        GenericValue::F64(v) => fmt_float(*v)?,
+        GenericValue::Duration(v) => fmt_float(v.as_secs_f64())?,
+        GenericValue::String(v) => fmt_string(v),
+        _ => return None,
+    })
+}
+
+/// Format a floating point value so that it is read back as one, within 20 characters,
+/// or `None` if it is not finite, which FITS can not represent.
+fn fmt_float(value: f64) -> Option<String> {
+    if !value.is_finite() {
+        return None;
+    }
+    let out = value.to_string();
+    Some(if out.len() > 20 {
+        format!("{value:E}")
+    } else if out.contains('.') {
+        out
+    } else {
+        out + ".0"
+    })
+}
+
+/// Quote a string value, escaping quotes and truncating it to fit on a header card.
+/// Non-ASCII characters are dropped.
+fn fmt_string(value: &str) -> String {
+    let mut out = String::new();
+    for c in value.chars().filter(char::is_ascii) {
+        // quotes are escaped by doubling them
+        let len = if c == '\'' { 2 } else { 1 };
+        if out.len() + len > STRING_LEN {
+            break;
+        }
+        out.extend(std::iter::repeat_n(c, len));
+    }
+    format!("'{out:<8}'")
+}
+
+/// Pad the output to a multiple of the block length.
+fn pad_block(out: &mut Vec<u8>, fill: u8) {
+    out.resize(out.len().div_ceil(BLOCK_LEN) * BLOCK_LEN, fill);
+}
+
+#[cfg(all(test, not(feature = "loom")))]
+mod test {
+    use super::*;
+    use crate::dummy::{DummyPattern, GenCamDummy};
+    use crate::{Capture, GenCam};
+    use refimage::{ColorSpace, ImageRef};
+    use std::time::{Duration, SystemTime};
+
+    /// Read the header cards of a FITS file.
+    fn header(bytes: &[u8]) -> Vec<String> {
+        bytes
+            .chunks_exact(CARD_LEN)
+            .map(|card| String::from_utf8(card.to_vec()).unwrap())
+            .take_while(|card| !card.starts_with("END "))
+            .collect()
+    }
+
+    fn value<'a>(cards: &'a [String], key: &str) -> Option<&'a str> {
+        cards
+            .iter()
+            .find(|card| card[..8].trim_end() == key)
+            .map(|card| card[10..].split(" / ").next().unwrap().trim())
+    }
+
+    #[test]
+    fn save_dummy_frame() {
+        let path = std::env::temp_dir().join(format!("generic_camera_{}.fits", std::process::id()));
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::Gradient);
+        cam.set_exposure(Duration::from_millis(10)).unwrap();
+        save_fits(&cam.capture_with_metadata().unwrap(), &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(bytes.len() % BLOCK_LEN, 0);
+        let cards = header(&bytes);
+        assert_eq!(value(&cards, "SIMPLE"), Some("T"));
+        assert_eq!(value(&cards, "BITPIX"), Some("8"));
+        assert_eq!(value(&cards, "NAXIS"), Some("3"));
+        assert_eq!(value(&cards, "NAXIS1"), Some("1920"));
+        assert_eq!(value(&cards, "NAXIS3"), Some("3"));
+        assert_eq!(value(&cards, metadata_keys::EXPOSURE_TIME), Some("0.01"));
+        assert_eq!(value(&cards, metadata_keys::X_OFFSET), Some("0"));
+        assert!(
+            value(&cards, metadata_keys::INSTRUMENT)
+                .unwrap()
+                .starts_with("'Dummy")
+        );
+        // the data starts on a block boundary, with the gradient of the first plane
+        let data = &bytes[(cards.len() + 1).div_ceil(36) * BLOCK_LEN..];
+        assert_eq!((data[0], data[1919]), (0, 255));
+    }
+
+    #[test]
+    fn unsigned_16_bit() {
+        let mut data = vec![0u16, 32768, 65535, 1];
+        let img = ImageRef::new(&mut data, 2, 2, ColorSpace::Gray).unwrap();
+        let img = GenericImageRef::new(SystemTime::UNIX_EPOCH, img.into());
+        let bytes = fits_bytes(&img);
+        let cards = header(&bytes);
+        assert_eq!(value(&cards, "BITPIX"), Some("16"));
+        assert_eq!(value(&cards, "NAXIS"), Some("2"));
+        assert_eq!(value(&cards, "BZERO"), Some("32768"));
+        assert_eq!(
+            &bytes[BLOCK_LEN..BLOCK_LEN + 8],
+            &[0x80, 0x00, 0x00, 0x00, 0x7f, 0xff, 0x80, 0x01]
+        );
+    }
+
+    #[test]
+    fn card_format() {
+        assert_eq!(card("BITPIX", "16", None).len(), CARD_LEN);
+        assert_eq!(
+            &card("BITPIX", "16", None)[..30],
+            "BITPIX  =                   16"
+        );
+        let name = card_value(&GenericValue::String("It's".into())).unwrap();
+        assert_eq!(name, "'It''s   '");
+        assert_eq!(fmt_float(1.0).as_deref(), Some("1.0"));
+        assert_eq!(fmt_float(1e-300).as_deref(), Some("1E-300"));
+        assert_eq!(fmt_float(f64::NAN), None);
+        assert_eq!(fmt_float(f64::NEG_INFINITY), None);
+    }
+
+    #[test]
+    fn long_string() {
+        // the closing quote is kept, and escaped quotes are not split
+        let long = format!("{}'{}", "a".repeat(66), "b".repeat(10));
+        let value = card_value(&GenericValue::String(long)).unwrap();
+        assert_eq!(value, format!("'{}'''", "a".repeat(66)));
+        let card = card(metadata_keys::INSTRUMENT, &value, Some("comment"));
+        assert_eq!(card.len(), CARD_LEN);
+        assert!(card.ends_with(&format!("{}'''", "a".repeat(66))));
+
+        let long = format!("{}'{}", "a".repeat(67), "b".repeat(10));
+        let value = card_value(&GenericValue::String(long)).unwrap();
+        assert_eq!(value, format!("'{}'", "a".repeat(67)));
+        let long = "c".repeat(100);
+        let value = card_value(&GenericValue::String(long)).unwrap();
+        assert_eq!(value.len(), STRING_LEN + 2);
+    }
+
+    #[test]
+    fn skip_nan() {
+        let mut data = vec![0u8; 4];
+        let img = ImageRef::new(&mut data, 2, 2, ColorSpace::Gray).unwrap();
+        let mut img = GenericImageRef::new(SystemTime::UNIX_EPOCH, img.into());
+        img.insert_key(metadata_keys::GAIN, f64::NAN).unwrap();
+        img.insert_key(metadata_keys::CCD_TEMP, -10.5f64).unwrap();
+        let cards = header(&fits_bytes(&img));
+        assert_eq!(value(&cards, metadata_keys::GAIN), None);
+        assert_eq!(value(&cards, metadata_keys::CCD_TEMP), Some("-10.5"));
+    }
+}
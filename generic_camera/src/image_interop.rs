@@ -0,0 +1,165 @@
+/*!
+ * # Image Crate Interoperability
+ * This module converts captured frames to the types of the [`image`] crate, e.g. for
+ * quick display or debugging. With the `image-export` feature, frames can also be saved
+ * to PNG or TIFF files.
+ */
+#[cfg(feature = "image-export")]
+use image::ImageFormat;
+use image::{DynamicImage, ImageBuffer, Pixel};
+use refimage::{ColorSpace, DynamicImageRef, GenericImageRef, ImageProps};
+#[cfg(feature = "image-export")]
+use std::path::Path;
+
+use crate::{GenCamError, GenCamResult};
+
+/// Convert a captured frame to an [`image::DynamicImage`], copying the pixel data.
+///
+/// 8- and 16-bit images in the [`ColorSpace::Gray`], [`ColorSpace::GrayAlpha`],
+/// [`ColorSpace::Rgb`] and [`ColorSpace::Rgba`] color spaces are supported. Metadata
+/// is not converted.
+///
+/// # Errors
+/// - [`GenCamError::InvalidImageType`] for other pixel types and color spaces, e.g.
+///   floating point or Bayer mosaic images.
+pub fn to_dynamic_image(img: &GenericImageRef<'_>) -> GenCamResult<DynamicImage> {
+    let (width, height, cspace) = (img.width() as u32, img.height() as u32, img.color_space());
+    match (img.get_image(), &cspace) {
+        (DynamicImageRef::U8(data), ColorSpace::Gray) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageLuma8)
+        }
+        (DynamicImageRef::U8(data), ColorSpace::GrayAlpha) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageLumaA8)
+        }
+        (DynamicImageRef::U8(data), ColorSpace::Rgb) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageRgb8)
+        }
+        (DynamicImageRef::U8(data), ColorSpace::Rgba) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageRgba8)
+        }
+        (DynamicImageRef::U16(data), ColorSpace::Gray) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageLuma16)
+        }
+        (DynamicImageRef::U16(data), ColorSpace::GrayAlpha) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageLumaA16)
+        }
+        (DynamicImageRef::U16(data), ColorSpace::Rgb) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageRgb16)
+        }
+        (DynamicImageRef::U16(data), ColorSpace::Rgba) => {
+            buffer(data.as_slice(), width, height).map(DynamicImage::ImageRgba16)
+        }
+        (DynamicImageRef::U8(_), _) | (DynamicImageRef::U16(_), _) => Err(
+            GenCamError::InvalidImageType(format!("Unsupported color space: {cspace:?}")),
+        ),
+        (DynamicImageRef::F32(_), _) => Err(GenCamError::InvalidImageType(
+            "Unsupported pixel type: f32".into(),
+        )),
+    }
+}
+
+/// Save a captured frame to a PNG (`.png`) or TIFF (`.tif`, `.tiff`) file, depending on
+/// the extension of `path`.
+///
+/// The frame is converted using [`to_dynamic_image`], and written with its bit depth;
+/// in particular, 16-bit frames are not truncated to 8 bits.
+///
+/// # Errors
+/// - [`GenCamError::InvalidPath`] if the extension is not supported, or the file can not
+///   be written.
+/// - [`GenCamError::InvalidImageType`] if the frame can not be converted.
+/// - [`GenCamError::InvalidFormat`] if the frame can not be encoded in the format.
+#[cfg(feature = "image-export")]
+#[cfg_attr(docsrs, doc(cfg(feature = "image-export")))]
+pub fn save_image(img: &GenericImageRef<'_>, path: &Path) -> GenCamResult<()> {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let format = match ext.as_deref() {
+        Some("png") => ImageFormat::Png,
+        Some("tif" | "tiff") => ImageFormat::Tiff,
+        _ => {
+            return Err(GenCamError::InvalidPath(format!(
+                "Unsupported image format: {}",
+                path.display()
+            )));
+        }
+    };
+    to_dynamic_image(img)?
+        .save_with_format(path, format)
+        .map_err(|e| match e {
+            image::ImageError::IoError(e) => {
+                GenCamError::InvalidPath(format!("{}: {e}", path.display()))
+            }
+            e => GenCamError::InvalidFormat(e.to_string()),
+        })
+}
+
+/// Copy interleaved pixel data into an image buffer.
+fn buffer<P: Pixel>(
+    data: &[P::Subpixel],
+    width: u32,
+    height: u32,
+) -> GenCamResult<ImageBuffer<P, Vec<P::Subpixel>>> {
+    ImageBuffer::from_raw(width, height, data.to_vec()).ok_or_else(|| {
+        GenCamError::InvalidImageType(format!(
+            "Buffer of length {} does not match a {width}x{height} image",
+            data.len()
+        ))
+    })
+}
+
+#[cfg(all(test, not(feature = "loom")))]
+mod test {
+    use super::*;
+    use crate::dummy::{DummyPattern, GenCamDummy};
+    use crate::{Capture, GenCam};
+    use std::time::Duration;
+
+    #[test]
+    fn dummy_rgb_frame() {
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::Gradient);
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        let img = to_dynamic_image(&cam.capture().unwrap()).unwrap();
+        let DynamicImage::ImageRgb8(img) = img else {
+            panic!("Expected an 8-bit RGB image");
+        };
+        assert_eq!(img.dimensions(), (1920, 1080));
+        assert_eq!(img.get_pixel(0, 0).0, [0, 0, 0]);
+        assert_eq!(img.get_pixel(1919, 500).0, [255, 255, 255]);
+    }
+
+    #[cfg(feature = "image-export")]
+    #[test]
+    fn save_tiff_roundtrip() {
+        use refimage::ImageRef;
+        use std::time::SystemTime;
+
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("generic_camera_{}.tiff", std::process::id()));
+        let mut cam = GenCamDummy::with_seed(42);
+        cam.set_pattern(DummyPattern::Checkerboard { size: 8 });
+        cam.set_exposure(Duration::from_millis(1)).unwrap();
+        save_image(&cam.capture().unwrap(), &path).unwrap();
+        let read = image::open(&path).unwrap();
+        assert_eq!((read.width(), read.height()), (1920, 1080));
+
+        // 16-bit data is preserved
+        let mut data: Vec<u16> = (0..12).map(|v| v * 5000).collect();
+        let img = ImageRef::new(&mut data, 4, 3, ColorSpace::Gray).unwrap();
+        let img = GenericImageRef::new(SystemTime::UNIX_EPOCH, img.into());
+        save_image(&img, &path).unwrap();
+        let DynamicImage::ImageLuma16(read) = image::open(&path).unwrap() else {
+            panic!("Expected a 16-bit gray image");
+        };
+        assert_eq!(read.get_pixel(3, 2).0, [55000]);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            save_image(&img, &dir.join("frame.fits")),
+            Err(GenCamError::InvalidPath(_))
+        ));
+    }
+}
@@ -5,8 +5,9 @@
  * This crate provides a generic interface for controlling cameras.
  *
  * ## Features
- * - `server`: Enables the generic camera server.
+ * - `server`: Enables the generic camera server and client.
  * - `dummy`: Enables the dummy camera implementation.
+ * - `tokio`: Enables `TokioSleep` for capturing asynchronously on the tokio runtime.
  *
  * ## Usage
  * To use the crate, add the following to your `Cargo.toml`:
@@ -17,24 +18,37 @@
  */
 
 pub use controls::GenCamCtrl;
-pub use refimage::GenericImage;
-use refimage::GenericImageRef;
+use controls::{AnalogCtrl, DeviceCtrl, ExposureCtrl, FrameTimeCtrl, SensorCtrl, TriggerCtrl};
+use refimage::{ColorSpace, DynamicImageRef, GenericImageRef, ImageProps};
+pub use refimage::{GenericImage, GenericImageOwned};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::hash::Hash;
-use std::{fmt::Display, time::Duration};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant, SystemTime};
 use thiserror::Error;
 
 pub use crate::property::{Property, PropertyError, PropertyType, PropertyValue};
 mod capture;
+#[cfg(feature = "server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+pub mod client;
 pub mod controls;
 pub use capture::*;
 #[cfg(any(feature = "dummy", test))]
 #[cfg_attr(docsrs, doc(cfg(feature = "dummy")))]
 pub mod dummy;
+#[cfg(feature = "fits")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fits")))]
+pub mod fits;
+#[cfg(feature = "image-interop")]
+#[cfg_attr(docsrs, doc(cfg(feature = "image-interop")))]
+pub mod image_interop;
+pub mod metadata_keys;
 pub mod property;
+pub mod stream;
 
-#[cfg(false)]
 #[cfg(feature = "server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
 pub mod server;
@@ -42,9 +56,19 @@ pub mod server;
 /// The version of the `generic_cam` crate.
 pub type GenCamResult<T> = std::result::Result<T, GenCamError>;
 
+/// The frames of a camera stream, see [`GenCam::start_stream`].
+pub type FrameStream = stream::StreamReceiver<GenCamResult<(FrameMeta, GenericImageOwned)>>;
+
+/// The definition and current value of each property of a camera, see [`GenCam::snapshot_properties`].
+pub type PropertySnapshot = HashMap<GenCamCtrl, (Property, Option<(PropertyValue, bool)>)>;
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Hash, Default)]
 /// This structure defines a region of interest.
 /// The region of interest is defined in the binned pixel space.
+///
+/// An all-zero region of interest means the full frame. Comparisons take the fields
+/// literally, so use [`GenCamRoi::normalized`] before comparing regions that may use
+/// either form.
 pub struct GenCamRoi {
     /// The minimum X coordinate (upper left, in binned pixel space).
     pub x_min: u16,
@@ -56,6 +80,197 @@ pub struct GenCamRoi {
     pub height: u16,
 }
 
+impl GenCamRoi {
+    /// Create a region of interest of size `width` x `height`, centered on a sensor of
+    /// size `sensor_w` x `sensor_h`.
+    ///
+    /// The size is limited to the sensor size. If the window can not be centered exactly,
+    /// the origin is rounded down, i.e. the window is shifted towards the upper left.
+    pub fn centered(width: u16, height: u16, sensor_w: u16, sensor_h: u16) -> GenCamRoi {
+        let width = width.min(sensor_w);
+        let height = height.min(sensor_h);
+        GenCamRoi {
+            x_min: (sensor_w - width) / 2,
+            y_min: (sensor_h - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Create a region of interest from coordinates relative to a sensor of size
+    /// `sensor_w` x `sensor_h`, e.g. `(0.25, 0.25, 0.5, 0.5)` for the center 50%.
+    ///
+    /// The edges of the window are rounded to the nearest pixel.
+    ///
+    /// # Errors
+    /// - [`GenCamError::OutOfBounds`] if a fraction is not in `[0, 1]`, or the window
+    ///   is empty or does not fit on the sensor.
+    pub fn from_fractions(
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        sensor_w: u16,
+        sensor_h: u16,
+    ) -> GenCamResult<GenCamRoi> {
+        if let Some(frac) = [x, y, w, h]
+            .into_iter()
+            .find(|frac| !(0.0..=1.0).contains(frac))
+        {
+            return Err(GenCamError::OutOfBounds(format!(
+                "Fraction {frac} is not in [0, 1]"
+            )));
+        }
+        let pixel = |frac: f32, size: u16| (frac as f64 * size as f64).round() as u32;
+        let (x_min, y_min) = (pixel(x, sensor_w), pixel(y, sensor_h));
+        let roi = GenCamRoi {
+            x_min: x_min as u16,
+            y_min: y_min as u16,
+            width: pixel(x + w, sensor_w).saturating_sub(x_min) as u16,
+            height: pixel(y + h, sensor_h).saturating_sub(y_min) as u16,
+        };
+        if roi.width == 0 || roi.height == 0 {
+            return Err(GenCamError::OutOfBounds(format!(
+                "{roi}: width and height must be non-zero"
+            )));
+        }
+        validate_roi(&roi, sensor_w, sensor_h)
+    }
+
+    /// Expand an all-zero region of interest into the full frame of a sensor of size
+    /// `sensor_w` x `sensor_h`. Any other region of interest is returned unchanged.
+    ///
+    /// Normalize regions before comparing them, e.g. to detect a change of the region
+    /// of interest.
+    pub fn normalized(&self, sensor_w: u16, sensor_h: u16) -> GenCamRoi {
+        if *self == GenCamRoi::default() {
+            GenCamRoi {
+                x_min: 0,
+                y_min: 0,
+                width: sensor_w,
+                height: sensor_h,
+            }
+        } else {
+            *self
+        }
+    }
+
+    /// Check if the pixel at `(x, y)` lies inside the region of interest.
+    ///
+    /// The geometry helpers take the fields literally: an all-zero region of interest
+    /// is empty rather than the full frame. Resolve the full frame convention with
+    /// [`validate_roi`] first if needed.
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        (self.x_min as u32..self.x_end()).contains(&(x as u32))
+            && (self.y_min as u32..self.y_end()).contains(&(y as u32))
+    }
+
+    /// Check if the region of interest overlaps `other` by at least one pixel.
+    ///
+    /// Regions that only touch along an edge do not intersect. See
+    /// [`GenCamRoi::contains`] for the handling of empty regions.
+    pub fn intersects(&self, other: &GenCamRoi) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Get the overlap of the region of interest with `other`, or `None` if they
+    /// do not intersect. See [`GenCamRoi::contains`] for the handling of empty regions.
+    pub fn intersection(&self, other: &GenCamRoi) -> Option<GenCamRoi> {
+        let x_min = self.x_min.max(other.x_min);
+        let y_min = self.y_min.max(other.y_min);
+        let x_end = self.x_end().min(other.x_end());
+        let y_end = self.y_end().min(other.y_end());
+        if x_end <= x_min as u32 || y_end <= y_min as u32 {
+            return None;
+        }
+        Some(GenCamRoi {
+            x_min,
+            y_min,
+            width: (x_end - x_min as u32) as u16,
+            height: (y_end - y_min as u32) as u16,
+        })
+    }
+
+    /// One past the maximum X coordinate.
+    fn x_end(&self) -> u32 {
+        self.x_min as u32 + self.width as u32
+    }
+
+    /// One past the maximum Y coordinate.
+    fn y_end(&self) -> u32 {
+        self.y_min as u32 + self.height as u32
+    }
+
+    /// Convert the region of interest to a binning space `factor` times coarser, e.g.
+    /// from unbinned pixels to 2x2 binned pixels with a `factor` of 2.
+    ///
+    /// All coordinates are divided by `factor` and rounded down. A `factor` of `0` is
+    /// treated as `1`.
+    pub fn scale_to_binning(&self, factor: u16) -> GenCamRoi {
+        let factor = factor.max(1);
+        GenCamRoi {
+            x_min: self.x_min / factor,
+            y_min: self.y_min / factor,
+            width: self.width / factor,
+            height: self.height / factor,
+        }
+    }
+
+    /// Align the region of interest to the `constraints` of a camera.
+    ///
+    /// The origin and the size are rounded down to a multiple of their steps, so the
+    /// aligned region never extends past the requested one, unless the size is raised to
+    /// the minimum size (rounded up to a multiple of the step). The result is not checked
+    /// against the sensor size, see [`validate_roi`].
+    pub fn align_to(&self, constraints: &RoiConstraints) -> GenCamRoi {
+        let down = |val: u16, step: u16| val - val % step.max(1);
+        let size = |val: u16, step: u16, min: u16| {
+            let step = step.max(1);
+            down(val, step).max(min.div_ceil(step).saturating_mul(step))
+        };
+        GenCamRoi {
+            x_min: down(self.x_min, constraints.x_step),
+            y_min: down(self.y_min, constraints.y_step),
+            width: size(self.width, constraints.width_step, constraints.min_width),
+            height: size(self.height, constraints.height_step, constraints.min_height),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Alignment constraints on a region of interest imposed by a camera, see
+/// [`GenCam::roi_constraints`].
+///
+/// All values are in binned pixel space. A step of `0` is treated as `1`.
+pub struct RoiConstraints {
+    /// The X coordinate of the origin must be a multiple of this.
+    pub x_step: u16,
+    /// The Y coordinate of the origin must be a multiple of this.
+    pub y_step: u16,
+    /// The width must be a multiple of this.
+    pub width_step: u16,
+    /// The height must be a multiple of this.
+    pub height_step: u16,
+    /// The minimum width.
+    pub min_width: u16,
+    /// The minimum height.
+    pub min_height: u16,
+}
+
+impl Default for RoiConstraints {
+    /// No constraints, i.e. all steps and minimum sizes are `1`.
+    fn default() -> Self {
+        RoiConstraints {
+            x_step: 1,
+            y_step: 1,
+            width_step: 1,
+            height_step: 1,
+            min_width: 1,
+            min_height: 1,
+        }
+    }
+}
+
 impl Display for GenCamRoi {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -66,6 +281,140 @@ impl Display for GenCamRoi {
     }
 }
 
+/// Validate a region of interest against the sensor dimensions.
+///
+/// An all-zero region of interest is treated as the full frame.
+///
+/// # Arguments
+/// - `roi`: The region of interest, in binned pixel space.
+/// - `sensor_w`: The sensor width, in binned pixel space.
+/// - `sensor_h`: The sensor height, in binned pixel space.
+///
+/// # Returns
+/// - The validated region of interest, or [`GenCamError::OutOfBounds`] if the region
+///   has a zero dimension or does not fit on the sensor.
+pub fn validate_roi(roi: &GenCamRoi, sensor_w: u16, sensor_h: u16) -> GenCamResult<GenCamRoi> {
+    if *roi == GenCamRoi::default() {
+        return Ok(roi.normalized(sensor_w, sensor_h));
+    }
+    if roi.width == 0 || roi.height == 0 {
+        return Err(GenCamError::OutOfBounds(format!(
+            "{roi}: width and height must be non-zero"
+        )));
+    }
+    if roi.x_min as u32 + roi.width as u32 > sensor_w as u32 {
+        return Err(GenCamError::OutOfBounds(format!(
+            "{roi}: exceeds sensor width {sensor_w}"
+        )));
+    }
+    if roi.y_min as u32 + roi.height as u32 > sensor_h as u32 {
+        return Err(GenCamError::OutOfBounds(format!(
+            "{roi}: exceeds sensor height {sensor_h}"
+        )));
+    }
+    Ok(*roi)
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Information about a captured frame, see [`GenCam::last_frame_meta`] and
+/// [`GenCam::start_stream`].
+pub struct FrameMeta {
+    /// The frame counter of the camera, incremented for each frame downloaded.
+    ///
+    /// A gap in the sequence of IDs indicates dropped frames.
+    pub frame_id: u64,
+    /// The timestamp of the frame reported by the camera, if available.
+    ///
+    /// The reference point is camera specific.
+    pub hardware_timestamp: Option<Duration>,
+    /// The time at which the frame was downloaded.
+    pub host_timestamp: SystemTime,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// The optional features supported by a camera, see [`GenCam::capabilities`].
+pub struct Capabilities {
+    /// The exposure time can be set ([`ExposureCtrl::ExposureTime`]).
+    pub exposure: bool,
+    /// The camera has a cooler ([`DeviceCtrl::CoolerTemp`] or [`DeviceCtrl::CoolerEnable`]).
+    pub cooling: bool,
+    /// Exposures can be triggered by a hardware signal, i.e. a hardware
+    /// [`TriggerMode`] is available.
+    pub hardware_trigger: bool,
+    /// Exposures can be triggered with [`GenCam::trigger_now`].
+    pub software_trigger: bool,
+    /// Frames can be streamed with [`GenCam::start_stream`].
+    pub streaming: bool,
+    /// The region of interest is read out by the sensor, instead of being cropped in
+    /// software.
+    pub hardware_roi: bool,
+    /// Pixels can be binned ([`SensorCtrl::BinningHorz`], [`SensorCtrl::BinningVert`]
+    /// or [`SensorCtrl::BinningBoth`]).
+    pub binning: bool,
+    /// The exposure time can be set automatically.
+    pub auto_exposure: bool,
+    /// The gain can be set automatically.
+    pub auto_gain: bool,
+}
+
+impl Capabilities {
+    /// Derive the capabilities of a camera from its properties, see
+    /// [`GenCam::list_properties`].
+    ///
+    /// Streaming and hardware regions of interest do not correspond to a control, and
+    /// are reported as unsupported.
+    pub fn from_properties(props: &HashMap<GenCamCtrl, Property>) -> Self {
+        let has = |ctrl: GenCamCtrl| props.contains_key(&ctrl);
+        let has_auto = |ctrl: GenCamCtrl| props.get(&ctrl).is_some_and(Property::supports_auto);
+        let hardware_trigger = props
+            .get(&GenCamCtrl::Trigger(TriggerCtrl::Mod))
+            .and_then(|prop| prop.get_variants().ok())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(PropertyValue::as_enum_str)
+            .any(|mode| {
+                matches!(
+                    TriggerMode::from(mode),
+                    TriggerMode::HardwareRising | TriggerMode::HardwareFalling
+                )
+            });
+        Capabilities {
+            exposure: has(GenCamCtrl::Exposure(ExposureCtrl::ExposureTime)),
+            cooling: has(GenCamCtrl::Device(DeviceCtrl::CoolerTemp))
+                || has(GenCamCtrl::Device(DeviceCtrl::CoolerEnable)),
+            hardware_trigger,
+            software_trigger: has(GenCamCtrl::Trigger(TriggerCtrl::SOFTWARE)),
+            streaming: false,
+            hardware_roi: false,
+            binning: [
+                SensorCtrl::BinningHorz,
+                SensorCtrl::BinningVert,
+                SensorCtrl::BinningBoth,
+            ]
+            .into_iter()
+            .any(|ctrl| has(GenCamCtrl::Sensor(ctrl))),
+            auto_exposure: has_auto(GenCamCtrl::Exposure(ExposureCtrl::ExposureTime))
+                || has(GenCamCtrl::Exposure(ExposureCtrl::Auto)),
+            auto_gain: has_auto(GenCamCtrl::Analog(AnalogCtrl::Gain))
+                || has(GenCamCtrl::Analog(AnalogCtrl::GainAuto)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The layout of a frame downloaded with [`GenCam::download_into`].
+pub struct ImageMeta {
+    /// The width of the frame, in pixels.
+    pub width: usize,
+    /// The height of the frame, in pixels.
+    pub height: usize,
+    /// The bit depth of each channel sample, which determines the size of the buffer
+    /// elements (see [`GenCamPixelBpp::element_bytes`]).
+    pub bpp: GenCamPixelBpp,
+    /// The color space of the frame.
+    pub color_space: ColorSpace,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 /// Defines the state of the camera.
 pub enum GenCamState {
@@ -79,7 +428,9 @@ pub enum GenCamState {
     ExposureFinished,
     /// Camera is downloading image.
     ///
-    /// Contains the percentage of the image downloaded, if available.
+    /// Contains the percentage of the image downloaded, if available. The percentage
+    /// should only increase during a download, and reach 100 once the image has been
+    /// downloaded, before the camera reports its next state.
     Downloading(Option<u32>),
     /// Error occurred.
     Errored(GenCamError),
@@ -87,6 +438,203 @@ pub enum GenCamState {
     Unknown,
 }
 
+impl GenCamState {
+    /// Check if the camera is idle.
+    pub fn is_idle(&self) -> bool {
+        matches!(self, GenCamState::Idle)
+    }
+
+    /// Check if the camera is exposing.
+    pub fn is_exposing(&self) -> bool {
+        matches!(self, GenCamState::Exposing(_))
+    }
+
+    /// Check if the exposure has finished.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, GenCamState::ExposureFinished)
+    }
+
+    /// Check if the camera is in an error state.
+    pub fn is_error(&self) -> bool {
+        matches!(self, GenCamState::Errored(_))
+    }
+
+    /// Get the progress of the current operation as a fraction in `[0, 1]`, if it can
+    /// be derived from the state alone.
+    ///
+    /// - [`GenCamState::Downloading`] returns the fraction of the image downloaded.
+    /// - [`GenCamState::ExposureFinished`] returns `1.0`.
+    /// - [`GenCamState::Exposing`] returns `None`, since the total exposure time is not
+    ///   part of the state.
+    pub fn progress(&self) -> Option<f32> {
+        match self {
+            GenCamState::Downloading(Some(pct)) => Some((*pct).min(100) as f32 / 100.0),
+            GenCamState::ExposureFinished => Some(1.0),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// The unit of a numeric exposure time, see [`GenCam::exposure_unit`].
+pub enum ExposureUnit {
+    /// Seconds.
+    Seconds,
+    /// Milliseconds.
+    Milliseconds,
+    /// Microseconds.
+    Microseconds,
+}
+
+impl ExposureUnit {
+    /// The number of units in a second.
+    fn per_second(self) -> f64 {
+        match self {
+            ExposureUnit::Seconds => 1.0,
+            ExposureUnit::Milliseconds => 1e3,
+            ExposureUnit::Microseconds => 1e6,
+        }
+    }
+
+    /// Convert an exposure time in this unit to a [`Duration`].
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidValue`] if `value` is negative or not finite.
+    pub fn duration_of(self, value: f64) -> GenCamResult<Duration> {
+        Duration::try_from_secs_f64(value / self.per_second()).map_err(|e| {
+            GenCamError::InvalidValue(format!("Invalid exposure time {value} ({self:?}): {e}"))
+        })
+    }
+
+    /// Convert a [`Duration`] to an exposure time in this unit.
+    pub fn value_of(self, exposure: Duration) -> f64 {
+        exposure.as_secs_f64() * self.per_second()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// A trigger mode, see [`GenCam::set_trigger_mode`].
+///
+/// Camera SDKs spell trigger modes differently ([`TriggerCtrl::Mod`] is a
+/// [`PropertyType::EnumStr`]); the common spellings are recognized case-insensitively,
+/// ignoring spaces, `-` and `_`:
+///
+/// - [`TriggerMode::Off`]: `Off`, `None`, `Normal`, `Disabled`, `Free Run`, `Continuous`.
+/// - [`TriggerMode::Software`]: `Software`, `Soft`, `Software Trigger`, `Soft Trigger`.
+/// - [`TriggerMode::HardwareRising`]: `Rising`, `Rising Edge`, `Edge Rising`,
+///   `Hardware`, `Hardware Rising`, `External`.
+/// - [`TriggerMode::HardwareFalling`]: `Falling`, `Falling Edge`, `Edge Falling`,
+///   `Hardware Falling`.
+///
+/// Anything else is reported as [`TriggerMode::Other`].
+pub enum TriggerMode {
+    /// The camera is free-running.
+    Off,
+    /// Exposures are triggered in software.
+    Software,
+    /// Exposures are triggered by the rising edge of a hardware signal.
+    HardwareRising,
+    /// Exposures are triggered by the falling edge of a hardware signal.
+    HardwareFalling,
+    /// A mode without a common spelling, with the string reported by the camera.
+    Other(String),
+}
+
+impl TriggerMode {
+    /// Check if the camera string `spelling` denotes this mode.
+    ///
+    /// [`TriggerMode::Other`] matches its string case-insensitively.
+    pub fn matches(&self, spelling: &str) -> bool {
+        match (self, TriggerMode::from(spelling)) {
+            (TriggerMode::Other(this), TriggerMode::Other(other)) => {
+                this.eq_ignore_ascii_case(&other)
+            }
+            (this, other) => *this == other,
+        }
+    }
+}
+
+impl From<&str> for TriggerMode {
+    fn from(spelling: &str) -> Self {
+        let key: String = spelling
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match key.as_str() {
+            "off" | "none" | "normal" | "disabled" | "freerun" | "continuous" => TriggerMode::Off,
+            "software" | "soft" | "softwaretrigger" | "softtrigger" => TriggerMode::Software,
+            "rising" | "risingedge" | "edgerising" | "hardware" | "hardwarerising" | "external" => {
+                TriggerMode::HardwareRising
+            }
+            "falling" | "fallingedge" | "edgefalling" | "hardwarefalling" => {
+                TriggerMode::HardwareFalling
+            }
+            _ => TriggerMode::Other(spelling.to_owned()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+/// A snapshot of the cooler status, see [`GenCam::cooler_status`].
+pub struct CoolerStatus {
+    /// The current temperature ([`DeviceCtrl::Temperature`]), in degrees Celsius.
+    pub temperature: f64,
+    /// The cooler setpoint ([`DeviceCtrl::CoolerTemp`]), in degrees Celsius, if available.
+    pub setpoint: Option<f64>,
+    /// The cooler power ([`DeviceCtrl::CoolerPower`]), in percent, if available.
+    pub power_percent: Option<f64>,
+    /// Whether the temperature is within 0.5 °C of the setpoint.
+    ///
+    /// Always `false` if the setpoint is not available.
+    pub at_setpoint: bool,
+}
+
+impl CoolerStatus {
+    /// The tolerance for [`CoolerStatus::at_setpoint`], in degrees Celsius.
+    pub const SETPOINT_TOLERANCE: f64 = 0.5;
+
+    /// Read the cooler status using `get_property`.
+    fn read(
+        get_property: impl Fn(GenCamCtrl) -> GenCamResult<(PropertyValue, bool)>,
+    ) -> GenCamResult<Self> {
+        let read = |ctrl: DeviceCtrl| -> GenCamResult<Option<f64>> {
+            let ctrl = GenCamCtrl::Device(ctrl);
+            let value = match get_property(ctrl) {
+                Ok((value, _)) => value,
+                Err(GenCamError::PropertyError {
+                    error: PropertyError::NotFound,
+                    ..
+                }) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            value
+                .as_f64()
+                .map(Some)
+                .ok_or_else(|| GenCamError::PropertyError {
+                    control: ctrl,
+                    error: PropertyError::InvalidControlType {
+                        expected: PropertyType::Float,
+                        received: value.get_type(),
+                    },
+                })
+        };
+        let temperature = read(DeviceCtrl::Temperature)?.ok_or(GenCamError::PropertyError {
+            control: GenCamCtrl::Device(DeviceCtrl::Temperature),
+            error: PropertyError::NotFound,
+        })?;
+        let setpoint = read(DeviceCtrl::CoolerTemp)?;
+        let power_percent = read(DeviceCtrl::CoolerPower)?;
+        Ok(CoolerStatus {
+            temperature,
+            setpoint,
+            power_percent,
+            at_setpoint: setpoint
+                .is_some_and(|sp| (temperature - sp).abs() < Self::SETPOINT_TOLERANCE),
+        })
+    }
+}
+
 /// A trait object for a camera unit.
 pub type AnyGenCam = Box<dyn GenCam>;
 /// A trait object for a camera info.
@@ -103,6 +651,150 @@ pub trait GenCamDriver {
     fn connect_device(&mut self, descriptor: &GenCamDescriptor) -> GenCamResult<AnyGenCam>;
     /// Connect to the first available device.
     fn connect_first_device(&mut self) -> GenCamResult<AnyGenCam>;
+
+    /// Connect to the device whose [`GenCamDescriptor::SERIAL_NUMBER`] info entry is `serial`.
+    ///
+    /// # Errors
+    /// - [`GenCamError::NoCamerasAvailable`] if no listed device has this serial number.
+    fn connect_by_serial(&mut self, serial: &str) -> GenCamResult<AnyGenCam> {
+        let desc = self
+            .list_devices()?
+            .into_iter()
+            .find(|desc| desc.get_str(GenCamDescriptor::SERIAL_NUMBER) == Some(serial))
+            .ok_or(GenCamError::NoCamerasAvailable)?;
+        self.connect_device(&desc)
+    }
+
+    /// Connect to the first device whose [`GenCamDescriptor::name`] is `name`.
+    ///
+    /// # Errors
+    /// - [`GenCamError::NoCamerasAvailable`] if no listed device has this name.
+    fn connect_by_name(&mut self, name: &str) -> GenCamResult<AnyGenCam> {
+        let desc = self
+            .list_devices()?
+            .into_iter()
+            .find(|desc| desc.name == name)
+            .ok_or(GenCamError::NoCamerasAvailable)?;
+        self.connect_device(&desc)
+    }
+
+    /// Get the devices that were plugged in or unplugged since the last call to
+    /// [`GenCamDriver::list_devices`] or [`GenCamDriver::poll_changes`].
+    ///
+    /// Drivers that support this keep the result of the last device listing, and
+    /// compare it to a fresh one using [`DeviceChanges::between`]. Before the first
+    /// listing, all available devices are reported as added. Support is optional;
+    /// the default implementation returns [`GenCamError::NotImplemented`].
+    fn poll_changes(&mut self) -> GenCamResult<DeviceChanges> {
+        Err(GenCamError::NotImplemented {
+            operation: "poll_changes".into(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+/// Devices plugged in or unplugged since the last device listing, see
+/// [`GenCamDriver::poll_changes`].
+pub struct DeviceChanges {
+    /// Devices that appeared.
+    pub added: Vec<GenCamDescriptor>,
+    /// Devices that disappeared.
+    pub removed: Vec<GenCamDescriptor>,
+}
+
+impl DeviceChanges {
+    /// Compute the changes from the `old` list of devices to the `new` one.
+    ///
+    /// Devices are matched with [`GenCamDescriptor::same_device`], so a device whose
+    /// [`GenCamDescriptor::info`] changed is not reported.
+    pub fn between(old: &[GenCamDescriptor], new: &[GenCamDescriptor]) -> Self {
+        let missing = |from: &[GenCamDescriptor], to: &[GenCamDescriptor]| {
+            from.iter()
+                .filter(|desc| !to.iter().any(|other| desc.same_device(other)))
+                .cloned()
+                .collect()
+        };
+        DeviceChanges {
+            added: missing(new, old),
+            removed: missing(old, new),
+        }
+    }
+
+    /// Check if no device was plugged in or unplugged.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+/// A snapshot of the property values of a camera, see [`GenCam::export_config`].
+///
+/// The values are serialized as a list of `(control, value, auto)` entries, so that the
+/// snapshot can be stored in formats that only support string map keys, such as JSON.
+pub struct CameraConfig {
+    /// The value of each property, and whether it is in auto mode.
+    #[serde(with = "config_entries")]
+    pub values: HashMap<GenCamCtrl, (PropertyValue, bool)>,
+}
+
+impl CameraConfig {
+    /// Get the controls whose values differ between `self` and `other`, as
+    /// `(control, old value, new value)`, where `self` is the old snapshot.
+    ///
+    /// Only controls present in both snapshots are compared, and changes of the auto
+    /// mode alone are not reported. Use [`CameraConfig::added`] and
+    /// [`CameraConfig::removed`] for controls present in only one of the snapshots.
+    /// The order of the entries is unspecified.
+    pub fn diff(&self, other: &CameraConfig) -> Vec<(GenCamCtrl, PropertyValue, PropertyValue)> {
+        self.values
+            .iter()
+            .filter_map(|(ctrl, (old, _))| match other.values.get(ctrl) {
+                Some((new, _)) if new != old => Some((*ctrl, old.clone(), new.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the controls present in `other` but not in `self`.
+    pub fn added(&self, other: &CameraConfig) -> Vec<GenCamCtrl> {
+        other.removed(self)
+    }
+
+    /// Get the controls present in `self` but not in `other`.
+    pub fn removed(&self, other: &CameraConfig) -> Vec<GenCamCtrl> {
+        self.values
+            .keys()
+            .filter(|ctrl| !other.values.contains_key(ctrl))
+            .copied()
+            .collect()
+    }
+}
+
+mod config_entries {
+    use super::{GenCamCtrl, PropertyValue};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S: Serializer>(
+        values: &HashMap<GenCamCtrl, (PropertyValue, bool)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        values
+            .iter()
+            .map(|(ctrl, (value, auto))| (ctrl, value, auto))
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<GenCamCtrl, (PropertyValue, bool)>, D::Error> {
+        let entries = Vec::<(GenCamCtrl, PropertyValue, bool)>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .map(|(ctrl, value, auto)| (ctrl, (value, auto)))
+            .collect())
+    }
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Default)]
@@ -118,6 +810,60 @@ pub struct GenCamDescriptor {
     pub info: HashMap<String, PropertyValue>,
 }
 
+impl GenCamDescriptor {
+    /// The key of the serial number ([`DeviceCtrl::SerialNumber`]) in [`GenCamDescriptor::info`],
+    /// stored as a [`PropertyValue::EnumStr`].
+    pub const SERIAL_NUMBER: &'static str = "Serial Number";
+
+    /// Get a string entry from [`GenCamDescriptor::info`].
+    ///
+    /// Returns `None` if the key is missing or the value is not a [`PropertyValue::EnumStr`].
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.info.get(key)?.as_enum_str()
+    }
+
+    /// Get a numeric entry from [`GenCamDescriptor::info`], converted with [`PropertyValue::as_f64`].
+    ///
+    /// Returns `None` if the key is missing or the value is not numeric.
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.info.get(key)?.as_f64()
+    }
+
+    /// Get an unsigned integer entry from [`GenCamDescriptor::info`].
+    ///
+    /// Returns `None` if the key is missing or the value is not a [`PropertyValue::Unsigned`].
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        self.info.get(key)?.as_u64()
+    }
+
+    /// Get a boolean entry from [`GenCamDescriptor::info`].
+    ///
+    /// Returns `None` if the key is missing or the value is not a [`PropertyValue::Bool`].
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.info.get(key)?.as_bool()
+    }
+
+    /// Check if two descriptors identify the same device, ignoring volatile entries of
+    /// [`GenCamDescriptor::info`] (e.g. a temperature reading).
+    ///
+    /// The identity of a device is its vendor and name, along with:
+    /// - its serial number ([`GenCamDescriptor::SERIAL_NUMBER`]), if both descriptors
+    ///   have one, since the ID may be reassigned when the devices are enumerated again;
+    /// - its ID otherwise.
+    pub fn same_device(&self, other: &Self) -> bool {
+        if self.vendor != other.vendor || self.name != other.name {
+            return false;
+        }
+        match (
+            self.get_str(Self::SERIAL_NUMBER),
+            other.get_str(Self::SERIAL_NUMBER),
+        ) {
+            (Some(this), Some(other)) => this == other,
+            _ => self.id == other.id,
+        }
+    }
+}
+
 /// The result of polling the exposure status
 #[derive(Debug)]
 pub enum PollExposure<'frame> {
@@ -152,10 +898,50 @@ pub trait GenCam: Send + std::fmt::Debug {
     /// Get optional capabilities of the camera.
     fn list_properties(&self) -> &HashMap<GenCamCtrl, Property>;
 
+    /// Get the properties of the camera ordered by zone, then by control, e.g. to log
+    /// the camera state in a deterministic order.
+    ///
+    /// The order follows [`GenCamCtrl::all_known`]; `Custom` controls come last in their
+    /// zone, ordered by name.
+    fn properties_sorted(&self) -> Vec<(GenCamCtrl, Property)> {
+        let mut props: Vec<_> = self
+            .list_properties()
+            .iter()
+            .map(|(ctrl, prop)| (*ctrl, prop.clone()))
+            .collect();
+        props.sort_by(|(a, _), (b, _)| a.stable_cmp(b));
+        props
+    }
+
+    /// Get the controls that support auto mode, see [`Property::supports_auto`].
+    ///
+    /// The controls are in no particular order.
+    fn auto_capable_controls(&self) -> Vec<GenCamCtrl> {
+        self.list_properties()
+            .iter()
+            .filter(|(_, prop)| prop.supports_auto())
+            .map(|(ctrl, _)| *ctrl)
+            .collect()
+    }
+
+    /// Get the optional features supported by the camera, e.g. to enable the matching
+    /// parts of a user interface.
+    ///
+    /// The default implementation uses [`Capabilities::from_properties`]. Drivers should
+    /// override this to report the capabilities that do not correspond to a control,
+    /// such as streaming.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_properties(self.list_properties())
+    }
+
     /// Get a property by name.
     fn get_property(&self, name: GenCamCtrl) -> GenCamResult<(PropertyValue, bool)>;
 
     /// Set a property by name.
+    ///
+    /// Implementations should check the value against the [`Property`] of the control
+    /// using [`Property::validate_write`] (or [`Property::validate`] and
+    /// [`Property::check_writable`]) before writing it.
     fn set_property(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()>;
     /// Set a property to a value that the device is allowed to choose automatically.
     ///
@@ -164,6 +950,127 @@ pub trait GenCam: Send + std::fmt::Debug {
     /// fallback.
     fn set_property_auto(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()>;
 
+    /// Turn auto mode of a control on or off, keeping its current value.
+    ///
+    /// The default implementation reads the current value with [`GenCam::get_property`],
+    /// and writes it back with [`GenCam::set_property_auto`] or [`GenCam::set_property`].
+    ///
+    /// # Errors
+    /// - [`PropertyError::NotFound`] if the camera does not have the control.
+    /// - [`PropertyError::AutoNotSupported`] if the control does not support auto mode.
+    fn set_auto(&mut self, ctrl: GenCamCtrl, enabled: bool) -> GenCamResult<()> {
+        let prop_err = |error| GenCamError::PropertyError {
+            control: ctrl,
+            error,
+        };
+        let prop = self
+            .list_properties()
+            .get(&ctrl)
+            .ok_or(prop_err(PropertyError::NotFound))?;
+        if !prop.supports_auto() {
+            return Err(prop_err(PropertyError::AutoNotSupported));
+        }
+        let (value, _) = self.get_property(ctrl)?;
+        if enabled {
+            self.set_property_auto(ctrl, &value)
+        } else {
+            self.set_property(ctrl, &value)
+        }
+    }
+
+    /// Get several properties at once.
+    ///
+    /// The default implementation calls [`GenCam::get_property`] for each control.
+    ///
+    /// # Returns
+    /// - The result for each control, in order.
+    fn get_properties(&self, names: &[GenCamCtrl]) -> Vec<GenCamResult<(PropertyValue, bool)>> {
+        names.iter().map(|&name| self.get_property(name)).collect()
+    }
+
+    /// Get the definition and the current value of every property in
+    /// [`GenCam::list_properties`], as `control -> (property, (value, auto))`.
+    ///
+    /// Properties whose value can not be read are included with a value of `None`.
+    /// The default implementation reads the values one by one; drivers that can read
+    /// all values at once should override this to return a consistent snapshot.
+    fn snapshot_properties(&self) -> GenCamResult<PropertySnapshot> {
+        Ok(self
+            .list_properties()
+            .iter()
+            .map(|(&ctrl, prop)| (ctrl, (prop.clone(), self.get_property(ctrl).ok())))
+            .collect())
+    }
+
+    /// Set several properties at once. Each item is `(control, value, auto)`, where
+    /// `auto` selects [`GenCam::set_property_auto`] over [`GenCam::set_property`].
+    ///
+    /// A failure to set one property does not prevent the remaining properties from
+    /// being set. The default implementation sets the properties one by one, in order;
+    /// drivers with transactional SDKs may override this.
+    ///
+    /// # Returns
+    /// - The result for each item, in order, or an error if the batch could not be
+    ///   attempted at all.
+    fn set_properties(
+        &mut self,
+        values: &[(GenCamCtrl, PropertyValue, bool)],
+    ) -> GenCamResult<Vec<GenCamResult<()>>> {
+        Ok(values
+            .iter()
+            .map(|(name, value, auto)| {
+                if *auto {
+                    self.set_property_auto(*name, value)
+                } else {
+                    self.set_property(*name, value)
+                }
+            })
+            .collect())
+    }
+
+    /// Take a snapshot of the values of all properties in [`GenCam::list_properties`].
+    ///
+    /// # Errors
+    /// - The first error returned by [`GenCam::get_property`].
+    fn export_config(&self) -> GenCamResult<CameraConfig> {
+        let values = self
+            .list_properties()
+            .keys()
+            .map(|&ctrl| Ok((ctrl, self.get_property(ctrl)?)))
+            .collect::<GenCamResult<_>>()?;
+        Ok(CameraConfig { values })
+    }
+
+    /// Apply a snapshot taken with [`GenCam::export_config`].
+    ///
+    /// Read-only properties are skipped. A failure to set one property does not prevent
+    /// the remaining properties from being set.
+    ///
+    /// # Returns
+    /// - The controls that could not be set, with the corresponding errors.
+    fn import_config(
+        &mut self,
+        config: &CameraConfig,
+    ) -> GenCamResult<Vec<(GenCamCtrl, GenCamError)>> {
+        let values: Vec<_> = config
+            .values
+            .iter()
+            .filter(|(ctrl, _)| {
+                !self
+                    .list_properties()
+                    .get(ctrl)
+                    .is_some_and(Property::is_readonly)
+            })
+            .map(|(&ctrl, (value, auto))| (ctrl, value.clone(), *auto))
+            .collect();
+        let res = self.set_properties(&values)?;
+        Ok(values
+            .into_iter()
+            .zip(res)
+            .filter_map(|((ctrl, ..), res)| res.err().map(|e| (ctrl, e)))
+            .collect())
+    }
+
     /// Cancel an ongoing exposure.
     fn cancel_capture(&self) -> GenCamResult<()>;
 
@@ -188,6 +1095,229 @@ pub trait GenCam: Send + std::fmt::Debug {
     /// Get the camera state.
     fn camera_state(&self) -> GenCamResult<GenCamState>;
 
+    /// Get the time remaining in the current exposure.
+    ///
+    /// The default implementation subtracts the elapsed time reported by
+    /// [`GenCamState::Exposing`] from the configured exposure time. Returns `None`
+    /// if the camera is not exposing, or if the elapsed or exposure time is unknown.
+    fn exposure_remaining(&self) -> GenCamResult<Option<Duration>> {
+        match self.camera_state()? {
+            GenCamState::Exposing(Some(elapsed)) => Ok(self
+                .get_exposure()
+                .ok()
+                .map(|exposure| exposure.saturating_sub(elapsed))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Subscribe to camera state transitions (e.g. [`GenCamState::Exposing`] followed
+    /// by [`GenCamState::ExposureFinished`]).
+    ///
+    /// Each call returns a new receiver that gets the transitions occurring after
+    /// the call. Support is optional; the default implementation returns
+    /// [`GenCamError::NotImplemented`].
+    fn subscribe_state(&self) -> GenCamResult<Receiver<GenCamState>> {
+        Err(GenCamError::NotImplemented {
+            operation: "subscribe_state".into(),
+        })
+    }
+
+    /// Wait until `target` matches the camera state, and return the matching state.
+    ///
+    /// The default implementation polls [`GenCam::camera_state`] every `poll`. A short
+    /// poll interval notices the state change sooner, at the cost of more calls into the
+    /// driver (and possibly the camera). Drivers that support [`GenCam::subscribe_state`]
+    /// can override this to wait on the state transitions instead of polling.
+    ///
+    /// # Errors
+    /// - [`GenCamError::TimedOut`] if the state did not match within `timeout`.
+    /// - The error of a [`GenCamState::Errored`] state, unless `target` matches it.
+    fn wait_for_state(
+        &self,
+        target: &dyn Fn(&GenCamState) -> bool,
+        timeout: Duration,
+        poll: Duration,
+    ) -> GenCamResult<GenCamState> {
+        let start = Instant::now();
+        loop {
+            let state = self.camera_state()?;
+            if target(&state) {
+                return Ok(state);
+            }
+            if let GenCamState::Errored(e) = state {
+                return Err(e);
+            }
+            let remaining = timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                return Err(GenCamError::TimedOut);
+            }
+            std::thread::sleep(poll.min(remaining));
+        }
+    }
+
+    /// Watch the value of a property.
+    ///
+    /// Each call returns a new receiver that gets the values `ctrl` is set to after
+    /// the call, including changes made by the driver, e.g. while the property is in
+    /// auto mode. Support is optional; the default implementation returns
+    /// [`GenCamError::NotImplemented`].
+    fn watch_property(&self, ctrl: GenCamCtrl) -> GenCamResult<Receiver<PropertyValue>> {
+        let _ = ctrl;
+        Err(GenCamError::NotImplemented {
+            operation: "watch_property".into(),
+        })
+    }
+
+    /// Get the color space of the frames produced by the camera.
+    ///
+    /// The default implementation returns [`ColorSpace::Gray`], for monochrome cameras.
+    /// Color cameras should override this, e.g. with [`ColorSpace::Bayer`] for cameras
+    /// that return the raw mosaic.
+    fn color_space(&self) -> GenCamResult<ColorSpace> {
+        Ok(ColorSpace::Gray)
+    }
+
+    /// Get the color filter array of the sensor, to [`demosaic`] raw frames.
+    ///
+    /// The default implementation returns the pattern of a [`ColorSpace::Bayer`] color
+    /// space ([`GenCam::color_space`]), and [`BayerPattern::None`] otherwise.
+    fn bayer_pattern(&self) -> GenCamResult<BayerPattern> {
+        Ok(BayerPattern::from(&self.color_space()?))
+    }
+
+    /// Get the size in bytes of a frame with the current region of interest, color
+    /// space and pixel format ([`SensorCtrl::PixelFormat`]).
+    ///
+    /// Drivers use this to allocate the buffer to download a frame into. The bit depth
+    /// of each channel is read with [`GenCam::current_bpp`], and cameras without a pixel
+    /// format property are taken to produce [`GenCamPixelBpp::Bpp8`] frames. The layout
+    /// matches [`validate_buffer_len`].
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidImageType`] if the number of channels of the color space is
+    ///   not known.
+    /// - Any error of [`GenCam::current_bpp`] other than a missing pixel format.
+    fn frame_buffer_len(&self) -> GenCamResult<usize> {
+        let bpp = match self.current_bpp() {
+            Ok(bpp) => bpp,
+            Err(GenCamError::PropertyError {
+                error: PropertyError::NotFound,
+                ..
+            }) => GenCamPixelBpp::Bpp8,
+            Err(e) => return Err(e),
+        };
+        let color = self.color_space()?;
+        let channels = channels_for(&color).ok_or_else(|| {
+            GenCamError::InvalidImageType(format!("Unknown number of channels: {color:?}"))
+        })?;
+        let roi = self.get_roi();
+        Ok(roi.width as usize * roi.height as usize * channels * bpp.element_bytes())
+    }
+
+    /// Capture a frame into `buf`, reusing its allocation, and return the layout of the
+    /// frame. This avoids allocating a buffer per frame, e.g. at high frame rates.
+    ///
+    /// The buffer is resized to the frame size, and only grows if it is too small. The
+    /// pixels are interleaved, with 16 bit samples in native byte order. The length of
+    /// the buffer matches the layout, see [`validate_buffer_len`].
+    ///
+    /// The default implementation copies a frame from [`Capture::capture`]; drivers
+    /// that can download directly into the buffer should override this.
+    ///
+    /// # Errors
+    /// - [`GenCamError::BufferTooSmall`] with the required length, if the buffer can not
+    ///   be grown.
+    /// - [`GenCamError::InvalidImageType`] for floating point frames.
+    fn download_into(&mut self, buf: &mut Vec<u8>) -> GenCamResult<ImageMeta> {
+        let img = Capture::capture(self)?;
+        let (width, height, color_space) = (img.width(), img.height(), img.color_space());
+        let mut reserve = |len: usize| {
+            buf.clear();
+            buf.try_reserve(len)
+                .map_err(|_| GenCamError::BufferTooSmall(len))
+        };
+        let bpp = match img.get_image() {
+            DynamicImageRef::U8(data) => {
+                reserve(data.as_slice().len())?;
+                buf.extend_from_slice(data.as_slice());
+                GenCamPixelBpp::Bpp8
+            }
+            DynamicImageRef::U16(data) => {
+                reserve(data.as_slice().len() * 2)?;
+                buf.extend(data.as_slice().iter().flat_map(|v| v.to_ne_bytes()));
+                GenCamPixelBpp::Bpp16
+            }
+            DynamicImageRef::F32(_) => {
+                return Err(GenCamError::InvalidImageType(
+                    "Unsupported pixel type: f32".into(),
+                ));
+            }
+        };
+        Ok(ImageMeta {
+            width,
+            height,
+            bpp,
+            color_space,
+        })
+    }
+
+    /// Borrow the raw bytes of the last frame downloaded, without copying them.
+    ///
+    /// The bytes have the layout of [`GenCam::download_into`] for the frame. The borrow
+    /// holds a shared reference to the camera, so the buffer can not be overwritten by
+    /// the next capture, or any other call taking `&mut self`, while it is alive. The
+    /// buffer belongs to the camera and is reused: copy the bytes to keep a frame past
+    /// the next capture. Frames delivered by [`GenCam::start_stream`] are not visible
+    /// here.
+    ///
+    /// Support is optional; the default implementation returns [`GenCamError::NotImplemented`].
+    ///
+    /// # Errors
+    /// - [`GenCamError::ExposureNotStarted`] if no frame has been downloaded yet.
+    fn peek_buffer(&self) -> GenCamResult<&[u8]> {
+        Err(GenCamError::NotImplemented {
+            operation: "peek_buffer".into(),
+        })
+    }
+
+    /// Get the [`FrameMeta`] of the last frame downloaded, or `None` if no frame has
+    /// been downloaded yet.
+    ///
+    /// Support is optional; the default implementation returns [`GenCamError::NotImplemented`].
+    fn last_frame_meta(&self) -> GenCamResult<Option<FrameMeta>> {
+        Err(GenCamError::NotImplemented {
+            operation: "last_frame_meta".into(),
+        })
+    }
+
+    /// Start continuous acquisition: the camera free-runs on a background thread, and
+    /// each frame (or capture error) is sent to the returned [`FrameStream`], along with
+    /// its [`FrameMeta`].
+    ///
+    /// The stream is bounded, and sending never waits for the consumer: if frames are
+    /// not received quickly enough, the oldest queued frames are dropped (see
+    /// [`stream::StreamReceiver::dropped`]). While streaming, the camera is busy, so
+    /// [`GenCam::set_property`], [`GenCam::start_exposure`] and the like may fail with
+    /// [`GenCamError::ExposureInProgress`]. The stream runs until [`GenCam::stop_stream`]
+    /// is called or the receiver is dropped.
+    ///
+    /// Support is optional; the default implementation returns [`GenCamError::NotImplemented`].
+    fn start_stream(&mut self) -> GenCamResult<FrameStream> {
+        Err(GenCamError::NotImplemented {
+            operation: "start_stream".into(),
+        })
+    }
+
+    /// Stop continuous acquisition started with [`GenCam::start_stream`], cancelling
+    /// the frame in progress. Frames already queued can still be received.
+    ///
+    /// Support is optional; the default implementation returns [`GenCamError::NotImplemented`].
+    fn stop_stream(&mut self) -> GenCamResult<()> {
+        Err(GenCamError::NotImplemented {
+            operation: "stop_stream".into(),
+        })
+    }
+
     /// Set the image region of interest (ROI).
     ///
     /// # Arguments
@@ -207,6 +1337,628 @@ pub trait GenCam: Send + std::fmt::Debug {
     /// # Returns
     /// - The region of interest.
     fn get_roi(&self) -> &GenCamRoi;
+
+    /// Get the alignment constraints on the region of interest, see [`GenCamRoi::align_to`].
+    ///
+    /// The default implementation returns [`RoiConstraints::default`], i.e. no constraints.
+    fn roi_constraints(&self) -> GenCamResult<RoiConstraints> {
+        Ok(RoiConstraints::default())
+    }
+
+    /// Get the native (unbinned) sensor size, in pixels.
+    ///
+    /// The default implementation reads [`SensorCtrl::WidthMax`] and [`SensorCtrl::HeightMax`].
+    /// Drivers that know the sensor size natively should override this.
+    ///
+    /// # Errors
+    /// - [`GenCamError::PropertyError`] with [`PropertyError::NotFound`] if the camera does
+    ///   not have the properties. This is not a [`GenCamError::Message`], so callers can
+    ///   match on the missing control.
+    fn sensor_size(&self) -> GenCamResult<(u32, u32)> {
+        let read = |ctrl: SensorCtrl| -> GenCamResult<u32> {
+            let ctrl = GenCamCtrl::Sensor(ctrl);
+            let value: u64 = property_as(ctrl, self.get_property(ctrl)?.0)?;
+            value.try_into().map_err(|_| GenCamError::PropertyError {
+                control: ctrl,
+                error: PropertyError::ValueNotSupported,
+            })
+        };
+        Ok((read(SensorCtrl::WidthMax)?, read(SensorCtrl::HeightMax)?))
+    }
+
+    /// Get the current binning factors ([`SensorCtrl::BinningHorz`], [`SensorCtrl::BinningVert`]).
+    ///
+    /// # Returns
+    /// - `(horizontal, vertical)` binning factors.
+    fn get_binning(&self) -> GenCamResult<(u32, u32)> {
+        let read = |ctrl: SensorCtrl| -> GenCamResult<u32> {
+            let ctrl = GenCamCtrl::Sensor(ctrl);
+            let value: u64 = property_as(ctrl, self.get_property(ctrl)?.0)?;
+            value.try_into().map_err(|_| GenCamError::PropertyError {
+                control: ctrl,
+                error: PropertyError::ValueNotSupported,
+            })
+        };
+        Ok((
+            read(SensorCtrl::BinningHorz)?,
+            read(SensorCtrl::BinningVert)?,
+        ))
+    }
+
+    /// Set the binning factors ([`SensorCtrl::BinningHorz`], [`SensorCtrl::BinningVert`]).
+    ///
+    /// The factors are validated against the property limits, which may be either
+    /// [`property::PropertyLims::Unsigned`] or [`property::PropertyLims::EnumUnsigned`].
+    ///
+    /// Since the region of interest is defined in binned pixel space, the default
+    /// implementation resets the region of interest to the full (binned) frame
+    /// after changing the binning, using [`GenCam::sensor_size`].
+    ///
+    /// # Errors
+    /// - [`PropertyError::ValueNotSupported`] if a factor is zero or not an allowed variant.
+    fn set_binning(&mut self, horz: u32, vert: u32) -> GenCamResult<()> {
+        let bins = [
+            (SensorCtrl::BinningHorz, horz),
+            (SensorCtrl::BinningVert, vert),
+        ];
+        for (ctrl, bin) in bins {
+            let ctrl = GenCamCtrl::Sensor(ctrl);
+            let value = PropertyValue::Unsigned(bin as u64);
+            if bin == 0 {
+                return Err(GenCamError::PropertyError {
+                    control: ctrl,
+                    error: PropertyError::ValueNotSupported,
+                });
+            }
+            if let Some(prop) = self.list_properties().get(&ctrl) {
+                prop.validate(&value)
+                    .map_err(|error| GenCamError::PropertyError {
+                        control: ctrl,
+                        error,
+                    })?;
+            }
+        }
+        for (ctrl, bin) in bins {
+            self.set_property(GenCamCtrl::Sensor(ctrl), &(bin as u64).into())?;
+        }
+        let (width, height) = self.sensor_size()?;
+        self.set_roi(&GenCamRoi {
+            x_min: 0,
+            y_min: 0,
+            width: u16::try_from(width / horz).unwrap_or(u16::MAX),
+            height: u16::try_from(height / vert).unwrap_or(u16::MAX),
+        })?;
+        Ok(())
+    }
+
+    /// Select the pixel format ([`SensorCtrl::PixelFormat`]) of the camera.
+    ///
+    /// The first format in `preferred` supported by the camera is selected. If none of
+    /// them is supported, the supported format with the highest bit depth is selected,
+    /// see [`max_bit_depth`].
+    ///
+    /// # Returns
+    /// - The selected pixel format.
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidFormat`] if the camera does not have a pixel format property.
+    fn select_pixel_format(
+        &mut self,
+        preferred: &[GenCamPixelBpp],
+    ) -> GenCamResult<GenCamPixelBpp> {
+        let ctrl = GenCamCtrl::Sensor(SensorCtrl::PixelFormat);
+        let variants: Vec<GenCamPixelBpp> = self
+            .list_properties()
+            .get(&ctrl)
+            .and_then(|prop| prop.get_variants().ok())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(PropertyValue::as_pixel_fmt)
+            .collect();
+        let fmt = preferred
+            .iter()
+            .copied()
+            .find(|fmt| variants.contains(fmt))
+            .or_else(|| max_bit_depth(&variants))
+            .ok_or_else(|| {
+                GenCamError::InvalidFormat("Camera has no pixel format property".into())
+            })?;
+        self.set_property(ctrl, &fmt.into())?;
+        Ok(fmt)
+    }
+
+    /// Get the bit depth of each channel of the current pixel format
+    /// ([`SensorCtrl::PixelFormat`]).
+    ///
+    /// The pixel format may be a [`PropertyValue::PixelFmt`], or the name of the format as
+    /// a [`PropertyValue::EnumStr`], e.g. `"Mono16"`, see [`parse_pixel_format`]. Both are
+    /// mapped to the bit depth of each channel: `"RGB8"` and [`GenCamPixelBpp::Bpp24`]
+    /// (8 bit RGB, see [`GenCamPixelBpp::element_bytes`]) are [`GenCamPixelBpp::Bpp8`].
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidFormat`] if the format name is not recognized.
+    /// - [`PropertyError::InvalidControlType`] if the property is of another type.
+    fn current_bpp(&self) -> GenCamResult<GenCamPixelBpp> {
+        let ctrl = GenCamCtrl::Sensor(SensorCtrl::PixelFormat);
+        match self.get_property(ctrl)?.0 {
+            PropertyValue::PixelFmt(GenCamPixelBpp::Bpp24 | GenCamPixelBpp::Bpp32) => {
+                Ok(GenCamPixelBpp::Bpp8)
+            }
+            PropertyValue::PixelFmt(bpp) => Ok(bpp),
+            PropertyValue::EnumStr(name) => parse_pixel_format(&name)
+                .map(|(bpp, _)| bpp)
+                .ok_or_else(|| GenCamError::InvalidFormat(format!("Unknown pixel format: {name}"))),
+            value => Err(GenCamError::PropertyError {
+                control: ctrl,
+                error: PropertyError::InvalidControlType {
+                    expected: PropertyType::PixelFmt,
+                    received: value.get_type(),
+                },
+            }),
+        }
+    }
+
+    /// Get the cooler status.
+    ///
+    /// The default implementation reads [`DeviceCtrl::Temperature`], [`DeviceCtrl::CoolerTemp`]
+    /// and [`DeviceCtrl::CoolerPower`]. Missing setpoint and power controls are reported as `None`.
+    ///
+    /// # Errors
+    /// - [`PropertyError::NotFound`] if the camera does not report its temperature.
+    fn cooler_status(&self) -> GenCamResult<CoolerStatus> {
+        CoolerStatus::read(|ctrl| self.get_property(ctrl))
+    }
+
+    /// Get the sensor temperature in degrees Celsius ([`DeviceCtrl::Temperature`]).
+    ///
+    /// # Errors
+    /// - [`GenCamError::PropertyError`] with [`PropertyError::NotFound`] if the camera does
+    ///   not report its temperature, rather than a [`GenCamError::Message`].
+    /// - [`PropertyError::InvalidControlType`] if the temperature is not a number.
+    fn temperature(&self) -> GenCamResult<f64> {
+        read_temperature(|ctrl| self.get_property(ctrl))
+    }
+
+    /// Get the frame rate in frames per second, derived from [`FrameTimeCtrl::FrameTime`].
+    ///
+    /// If [`FrameTimeCtrl::Auto`] is enabled, this reports the frame rate corresponding
+    /// to the frame time currently reported by the camera.
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidValue`] if the frame time is zero.
+    fn get_frame_rate(&self) -> GenCamResult<f64> {
+        let ctrl = GenCamCtrl::FrameTime(FrameTimeCtrl::FrameTime);
+        let frame_time: Duration = property_as(ctrl, self.get_property(ctrl)?.0)?;
+        if frame_time.is_zero() {
+            return Err(GenCamError::InvalidValue("Frame time is zero".into()));
+        }
+        Ok(1.0 / frame_time.as_secs_f64())
+    }
+
+    /// Set the frame rate in frames per second, by setting [`FrameTimeCtrl::FrameTime`].
+    ///
+    /// The frame time is clamped to the property limits and snapped to the nearest
+    /// multiple of the property step (see [`Property::clamp`]), so the effective frame
+    /// rate can differ noticeably from `fps` when the step is coarse. Use
+    /// [`GenCam::get_frame_rate`] to read back the effective frame rate.
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidValue`] if `fps` is not a positive, finite number.
+    fn set_frame_rate(&mut self, fps: f64) -> GenCamResult<()> {
+        let ctrl = GenCamCtrl::FrameTime(FrameTimeCtrl::FrameTime);
+        if !fps.is_finite() || fps <= 0.0 {
+            return Err(GenCamError::InvalidValue(format!(
+                "Invalid frame rate: {fps}"
+            )));
+        }
+        let frame_time = Duration::try_from_secs_f64(fps.recip())
+            .map_err(|e| GenCamError::InvalidValue(format!("Invalid frame rate {fps}: {e}")))?;
+        let mut value = frame_time.into();
+        if let Some(prop) = self.list_properties().get(&ctrl) {
+            value = prop
+                .clamp(&value)
+                .map_err(|error| GenCamError::PropertyError {
+                    control: ctrl,
+                    error,
+                })?;
+        }
+        self.set_property(ctrl, &value)
+    }
+
+    /// Get the unit of the exposure time ([`ExposureCtrl::ExposureTime`]) when the camera
+    /// reports it as a number instead of a [`PropertyType::Duration`].
+    ///
+    /// The default implementation returns [`ExposureUnit::Microseconds`], following
+    /// the GenICam convention.
+    fn exposure_unit(&self) -> ExposureUnit {
+        ExposureUnit::Microseconds
+    }
+
+    /// Get the exposure time ([`ExposureCtrl::ExposureTime`]).
+    ///
+    /// Numeric exposure times are converted using [`GenCam::exposure_unit`].
+    fn get_exposure(&self) -> GenCamResult<Duration> {
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        match self.get_property(ctrl)?.0 {
+            PropertyValue::Duration(exposure) => Ok(exposure),
+            value => match value.as_f64() {
+                Some(exposure) => self.exposure_unit().duration_of(exposure),
+                None => property_as(ctrl, value),
+            },
+        }
+    }
+
+    /// Set the exposure time ([`ExposureCtrl::ExposureTime`]).
+    ///
+    /// If the camera reports the exposure time as a number, the exposure is converted
+    /// using [`GenCam::exposure_unit`], and rounded for integer properties.
+    fn set_exposure(&mut self, exposure: Duration) -> GenCamResult<()> {
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let value = self.exposure_unit().value_of(exposure);
+        let value = match self.list_properties().get(&ctrl).map(Property::get_type) {
+            Some(PropertyType::Float) => PropertyValue::Float(value),
+            Some(PropertyType::Int) => PropertyValue::Int(value.round() as i64),
+            Some(PropertyType::Unsigned) => PropertyValue::Unsigned(value.round() as u64),
+            _ => exposure.into(),
+        };
+        self.set_property(ctrl, &value)
+    }
+
+    /// Get the minimum and maximum exposure time ([`ExposureCtrl::ExposureTime`]).
+    ///
+    /// Numeric limits are converted using [`GenCam::exposure_unit`], like
+    /// [`GenCam::get_exposure`].
+    ///
+    /// # Errors
+    /// - [`GenCamError::PropertyError`] with [`PropertyError::NotFound`] if the camera does
+    ///   not have an exposure time, rather than a [`GenCamError::Message`].
+    /// - [`PropertyError::NotNumber`] if the exposure time has no limits.
+    fn exposure_limits(&self) -> GenCamResult<(Duration, Duration)> {
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let prop = self
+            .list_properties()
+            .get(&ctrl)
+            .ok_or(GenCamError::PropertyError {
+                control: ctrl,
+                error: PropertyError::NotFound,
+            })?;
+        let duration =
+            |value: Result<PropertyValue, PropertyError>| match value.map_err(|error| {
+                GenCamError::PropertyError {
+                    control: ctrl,
+                    error,
+                }
+            })? {
+                PropertyValue::Duration(exposure) => Ok(exposure),
+                value => match value.as_f64() {
+                    Some(exposure) => self.exposure_unit().duration_of(exposure),
+                    None => property_as(ctrl, value),
+                },
+            };
+        Ok((duration(prop.get_min())?, duration(prop.get_max())?))
+    }
+
+    /// Get the gain ([`AnalogCtrl::Gain`]).
+    fn get_gain(&self) -> GenCamResult<f64> {
+        let ctrl = GenCamCtrl::Analog(AnalogCtrl::Gain);
+        property_as(ctrl, self.get_property(ctrl)?.0)
+    }
+
+    /// Set the gain ([`AnalogCtrl::Gain`]).
+    fn set_gain(&mut self, gain: f64) -> GenCamResult<()> {
+        self.set_property(GenCamCtrl::Analog(AnalogCtrl::Gain), &gain.into())
+    }
+
+    /// Get the trigger mode ([`TriggerCtrl::Mod`]), see [`TriggerMode`].
+    fn get_trigger_mode(&self) -> GenCamResult<TriggerMode> {
+        let ctrl = GenCamCtrl::Trigger(TriggerCtrl::Mod);
+        let (value, _) = self.get_property(ctrl)?;
+        value
+            .as_enum_str()
+            .map(TriggerMode::from)
+            .ok_or_else(|| GenCamError::PropertyError {
+                control: ctrl,
+                error: PropertyError::InvalidControlType {
+                    expected: PropertyType::EnumStr,
+                    received: value.get_type(),
+                },
+            })
+    }
+
+    /// Set the trigger mode ([`TriggerCtrl::Mod`]).
+    ///
+    /// The default implementation sets the first variant of the property that
+    /// [`TriggerMode::matches`] `mode`.
+    ///
+    /// # Errors
+    /// - [`PropertyError::NotFound`] if the camera has no trigger mode control.
+    /// - [`PropertyError::ValueNotSupported`] if no variant matches `mode`.
+    fn set_trigger_mode(&mut self, mode: TriggerMode) -> GenCamResult<()> {
+        let ctrl = GenCamCtrl::Trigger(TriggerCtrl::Mod);
+        let prop_err = |error| GenCamError::PropertyError {
+            control: ctrl,
+            error,
+        };
+        let value = self
+            .list_properties()
+            .get(&ctrl)
+            .ok_or(prop_err(PropertyError::NotFound))?
+            .get_variants()
+            .map_err(prop_err)?
+            .into_iter()
+            .find(|value| value.as_enum_str().is_some_and(|s| mode.matches(s)))
+            .ok_or(prop_err(PropertyError::ValueNotSupported))?;
+        self.set_property(ctrl, &value)
+    }
+
+    /// Fire a software trigger, e.g. after selecting [`TriggerMode::Software`] with
+    /// [`GenCam::set_trigger_mode`].
+    ///
+    /// The default implementation writes [`PropertyValue::Command`] to
+    /// [`TriggerCtrl::SOFTWARE`]. Drivers with a native software trigger should
+    /// override this.
+    ///
+    /// # Errors
+    /// - [`GenCamError::NotImplemented`] if the camera does not have a software trigger.
+    fn trigger_now(&mut self) -> GenCamResult<()> {
+        let ctrl = GenCamCtrl::Trigger(TriggerCtrl::SOFTWARE);
+        if !self.list_properties().contains_key(&ctrl) {
+            return Err(GenCamError::NotImplemented {
+                operation: "trigger_now".into(),
+            });
+        }
+        self.set_property(ctrl, &PropertyValue::Command)
+    }
+
+    /// Reinitialize the camera, e.g. after an operation returned
+    /// [`GenCamError::CameraRemoved`] because of a transient USB glitch.
+    ///
+    /// This reopens the device described by [`GenCam::info`] in place, without going
+    /// back through the [`GenCamDriver`]. Any exposure in progress is lost. Drivers try
+    /// to preserve the current property values, but they may be reset to their
+    /// defaults if the hardware requires it.
+    ///
+    /// # Errors
+    /// - [`GenCamError::NotImplemented`] if the camera does not support reconnecting.
+    /// - [`GenCamError::CameraRemoved`] if the device is still unavailable.
+    fn reconnect(&mut self) -> GenCamResult<()> {
+        Err(GenCamError::NotImplemented {
+            operation: "reconnect".into(),
+        })
+    }
+
+    /// Abort any capture in progress and return the camera to a known, idle state.
+    ///
+    /// Unlike [`GenCam::cancel_capture`], this also clears any errored state. The default
+    /// implementation cancels the capture, and issues [`DeviceCtrl::Reset`] if the camera
+    /// supports it. Drivers should override this to reset their own state, and may
+    /// restore the property values to their defaults.
+    fn reset(&mut self) -> GenCamResult<()> {
+        match self.cancel_capture() {
+            Ok(()) | Err(GenCamError::ExposureNotStarted) => {}
+            Err(e) => return Err(e),
+        }
+        let ctrl = GenCamCtrl::Device(DeviceCtrl::Reset);
+        if self.list_properties().contains_key(&ctrl) {
+            self.set_property(ctrl, &PropertyValue::Command)?;
+        }
+        Ok(())
+    }
+
+    /// Set the gain ([`AnalogCtrl::Gain`]) in decibels.
+    ///
+    /// The default implementation assumes the raw gain is a linear amplitude factor, and
+    /// that the minimum of the gain property corresponds to 0 dB (see
+    /// [`gain_db_to_raw`]). The raw gain is clamped to the property limits, and rounded
+    /// to the nearest step of integer gain properties. Drivers whose
+    /// cameras report gain in other units (e.g. already in dB, or with a different
+    /// reference point) should override this.
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidValue`] if `db` is not finite, or the minimum gain is not
+    ///   positive and can not serve as the reference.
+    fn set_gain_db(&mut self, db: f64) -> GenCamResult<()> {
+        let ctrl = GenCamCtrl::Analog(AnalogCtrl::Gain);
+        let prop_err = |error| GenCamError::PropertyError {
+            control: ctrl,
+            error,
+        };
+        if !db.is_finite() {
+            return Err(GenCamError::InvalidValue(format!("Invalid gain: {db} dB")));
+        }
+        let prop = self
+            .list_properties()
+            .get(&ctrl)
+            .ok_or(prop_err(PropertyError::NotFound))?;
+        let reference = prop
+            .get_min()
+            .map_err(prop_err)?
+            .as_f64()
+            .ok_or(prop_err(PropertyError::NotNumber))?;
+        if reference <= 0.0 {
+            return Err(GenCamError::InvalidValue(format!(
+                "Minimum gain {reference} can not be used as a 0 dB reference"
+            )));
+        }
+        let max = prop
+            .get_max()
+            .map_err(prop_err)?
+            .as_f64()
+            .ok_or(prop_err(PropertyError::NotNumber))?;
+        let raw = gain_db_to_raw(db, reference).clamp(reference, max);
+        let value = prop
+            .make_value_from_f64(raw)
+            .and_then(|value| prop.clamp(&value))
+            .map_err(prop_err)?;
+        self.set_property(ctrl, &value)
+    }
+
+    /// Set the white balance ratios of the red, green and blue channels.
+    ///
+    /// The default implementation selects each channel with [`AnalogCtrl::BalanceRatioSel`]
+    /// and sets its [`AnalogCtrl::BalanceRatio`], then restores the selected channel. The
+    /// channels are selected by the variants of the selector named `"Red"`, `"Green"` and
+    /// `"Blue"` (or `"R"`, `"G"` and `"B"`), case-insensitively.
+    ///
+    /// # Errors
+    /// - [`GenCamError::NotImplemented`] if the camera does not have the balance ratio
+    ///   controls.
+    /// - [`PropertyError::ValueNotSupported`] if the selector does not have a variant for
+    ///   every channel, or a ratio can not be set.
+    /// - [`PropertyError::ValueOutOfRange`] if a ratio is out of range.
+    ///
+    /// All ratios are validated before any channel is selected, so nothing is written
+    /// if one is invalid. The selected channel is restored even if setting a ratio fails.
+    fn set_white_balance(&mut self, r: f64, g: f64, b: f64) -> GenCamResult<()> {
+        let (sel, ratio) = (
+            GenCamCtrl::Analog(AnalogCtrl::BalanceRatioSel),
+            GenCamCtrl::Analog(AnalogCtrl::BalanceRatio),
+        );
+        let channels = balance_channels(self.list_properties(), "set_white_balance")?;
+        let prop = &self.list_properties()[&ratio];
+        let [r, g, b] = [r, g, b].map(|v| {
+            prop.make_value_from_f64(v)
+                .map_err(|error| GenCamError::PropertyError {
+                    control: ratio,
+                    error,
+                })
+        });
+        let values = [r?, g?, b?];
+        let (selected, _) = self.get_property(sel)?;
+        let res = channels
+            .iter()
+            .zip(&values)
+            .try_for_each(|(channel, value)| {
+                self.set_property(sel, channel)?;
+                self.set_property(ratio, value)
+            });
+        let restored = self.set_property(sel, &selected);
+        res?;
+        restored
+    }
+
+    /// Get the white balance ratios of the red, green and blue channels.
+    ///
+    /// The default implementation reads [`AnalogCtrl::BalanceRatio`] for each channel
+    /// like [`GenCam::set_white_balance`], then restores the selected channel.
+    ///
+    /// # Errors
+    /// - [`GenCamError::NotImplemented`] if the camera does not have the balance ratio
+    ///   controls.
+    /// - [`PropertyError::ValueNotSupported`] if the selector does not have a variant for
+    ///   every channel.
+    /// - [`PropertyError::NotNumber`] if a ratio is not a number.
+    fn get_white_balance(&mut self) -> GenCamResult<(f64, f64, f64)> {
+        let (sel, ratio) = (
+            GenCamCtrl::Analog(AnalogCtrl::BalanceRatioSel),
+            GenCamCtrl::Analog(AnalogCtrl::BalanceRatio),
+        );
+        let channels = balance_channels(self.list_properties(), "get_white_balance")?;
+        let (selected, _) = self.get_property(sel)?;
+        let mut values = [0.0; 3];
+        let res: GenCamResult<()> =
+            channels
+                .iter()
+                .zip(&mut values)
+                .try_for_each(|(channel, value)| {
+                    self.set_property(sel, channel)?;
+                    *value =
+                        self.get_property(ratio)?
+                            .0
+                            .as_f64()
+                            .ok_or(GenCamError::PropertyError {
+                                control: ratio,
+                                error: PropertyError::NotNumber,
+                            })?;
+                    Ok(())
+                });
+        let restored = self.set_property(sel, &selected);
+        res?;
+        restored?;
+        Ok((values[0], values[1], values[2]))
+    }
+}
+
+/// Convert a raw gain to decibels relative to the raw gain `ref_raw`, treating raw gains
+/// as linear amplitude factors: `20 * log10(raw / ref_raw)`.
+pub fn gain_raw_to_db(raw: f64, ref_raw: f64) -> f64 {
+    20.0 * (raw / ref_raw).log10()
+}
+
+/// Convert a gain in decibels relative to the raw gain `ref_raw` to a raw gain; the
+/// inverse of [`gain_raw_to_db`].
+pub fn gain_db_to_raw(db: f64, ref_raw: f64) -> f64 {
+    ref_raw * 10f64.powf(db / 20.0)
+}
+
+/// Read [`DeviceCtrl::Temperature`] in degrees Celsius using `get_property`, for
+/// [`GenCam::temperature`] and [`GenCamInfo::temperature`].
+fn read_temperature(
+    get_property: impl Fn(GenCamCtrl) -> GenCamResult<(PropertyValue, bool)>,
+) -> GenCamResult<f64> {
+    let ctrl = GenCamCtrl::Device(DeviceCtrl::Temperature);
+    let (value, _) = get_property(ctrl)?;
+    value.as_f64().ok_or_else(|| GenCamError::PropertyError {
+        control: ctrl,
+        error: PropertyError::InvalidControlType {
+            expected: PropertyType::Float,
+            received: value.get_type(),
+        },
+    })
+}
+
+/// Convert a property value obtained from `ctrl`, mapping a type mismatch to [`GenCamError::PropertyError`].
+fn property_as<T: TryFrom<PropertyValue, Error = PropertyError>>(
+    ctrl: GenCamCtrl,
+    value: PropertyValue,
+) -> GenCamResult<T> {
+    value
+        .try_into()
+        .map_err(|error| GenCamError::PropertyError {
+            control: ctrl,
+            error,
+        })
+}
+
+/// Get the variants of [`AnalogCtrl::BalanceRatioSel`] that select the red, green and blue
+/// channels, see [`GenCam::set_white_balance`].
+fn balance_channels(
+    props: &HashMap<GenCamCtrl, Property>,
+    operation: &str,
+) -> GenCamResult<[PropertyValue; 3]> {
+    let sel = GenCamCtrl::Analog(AnalogCtrl::BalanceRatioSel);
+    let (Some(selector), true) = (
+        props.get(&sel),
+        props.contains_key(&GenCamCtrl::Analog(AnalogCtrl::BalanceRatio)),
+    ) else {
+        return Err(GenCamError::NotImplemented {
+            operation: operation.into(),
+        });
+    };
+    let variants = selector
+        .get_variants()
+        .map_err(|error| GenCamError::PropertyError {
+            control: sel,
+            error,
+        })?;
+    let find = |names: [&str; 2]| {
+        variants
+            .iter()
+            .find(|v| {
+                v.as_enum_str()
+                    .is_some_and(|v| names.iter().any(|name| v.eq_ignore_ascii_case(name)))
+            })
+            .cloned()
+            .ok_or(GenCamError::PropertyError {
+                control: sel,
+                error: PropertyError::ValueNotSupported,
+            })
+    };
+    Ok([
+        find(["red", "r"])?,
+        find(["green", "g"])?,
+        find(["blue", "b"])?,
+    ])
 }
 
 /// Trait for obtaining camera information and cancelling any ongoing image capture.
@@ -242,9 +1994,32 @@ pub trait GenCamInfo: Send + Sync + std::fmt::Debug {
     /// or if the device does not support automatically setting the given property, `value` may be used as a
     /// fallback.
     fn set_property_auto(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()>;
+
+    /// Check if the camera behind this handle is still connected.
+    ///
+    /// Returns `false` once the device is removed or the corresponding [`GenCam`] is
+    /// dropped, so that housekeeping threads can exit. Drivers that can not detect
+    /// this return `true`, which is the default.
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    /// Get the cooler status. See [`GenCam::cooler_status`].
+    fn cooler_status(&self) -> GenCamResult<CoolerStatus> {
+        CoolerStatus::read(|ctrl| self.get_property(ctrl))
+    }
+
+    /// Get the sensor temperature in degrees Celsius. See [`GenCam::temperature`].
+    ///
+    /// # Errors
+    /// - [`GenCamError::PropertyError`] with [`PropertyError::NotFound`] if the camera does
+    ///   not report its temperature.
+    fn temperature(&self) -> GenCamResult<f64> {
+        read_temperature(|ctrl| self.get_property(ctrl))
+    }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
 /// Pixel bit depth.
 pub enum GenCamPixelBpp {
@@ -254,14 +2029,156 @@ pub enum GenCamPixelBpp {
     Bpp10 = 10,
     /// 12 bits per pixel.
     Bpp12 = 12,
-    /// 14 bits per pixel.
-    Bpp16 = 16,
     /// 16 bits per pixel.
+    Bpp16 = 16,
+    /// 24 bits per pixel.
     Bpp24 = 24,
     /// 32 bits per pixel.
     Bpp32 = 32,
 }
 
+impl GenCamPixelBpp {
+    /// Get the number of bits per pixel.
+    pub fn bits(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Get the number of bytes per pixel, rounded up to the next whole byte.
+    ///
+    /// e.g. 10 and 12 bit pixels are stored in 2 bytes.
+    pub fn bytes_per_pixel(&self) -> u32 {
+        self.bits().div_ceil(8)
+    }
+
+    /// Get the size in bytes of one element (channel sample) of an image buffer.
+    ///
+    /// 10 to 16 bit pixels are stored in 2 byte elements. 24 and 32 bit pixels are
+    /// taken to be 8 bit RGB and RGBA pixels, and are stored in 1 byte elements like
+    /// 8 bit pixels.
+    pub fn element_bytes(&self) -> usize {
+        match self {
+            GenCamPixelBpp::Bpp8 | GenCamPixelBpp::Bpp24 | GenCamPixelBpp::Bpp32 => 1,
+            GenCamPixelBpp::Bpp10 | GenCamPixelBpp::Bpp12 | GenCamPixelBpp::Bpp16 => 2,
+        }
+    }
+
+    /// Convert from a number of bits per pixel, returning `None` if the
+    /// bit depth is not known.
+    ///
+    /// Unlike the `From<u32>` implementation, this does not fall back to
+    /// [`GenCamPixelBpp::Bpp8`].
+    pub fn try_from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            8 => Some(GenCamPixelBpp::Bpp8),
+            10 => Some(GenCamPixelBpp::Bpp10),
+            12 => Some(GenCamPixelBpp::Bpp12),
+            16 => Some(GenCamPixelBpp::Bpp16),
+            24 => Some(GenCamPixelBpp::Bpp24),
+            32 => Some(GenCamPixelBpp::Bpp32),
+            _ => None,
+        }
+    }
+
+    /// Compare two pixel formats by their number of bits per pixel, see
+    /// [`GenCamPixelBpp::bits`].
+    ///
+    /// Unlike the derived [`Ord`], this does not depend on the order or
+    /// discriminants of the variants.
+    pub fn bit_depth_ord(&self, other: &Self) -> std::cmp::Ordering {
+        self.bits().cmp(&other.bits())
+    }
+}
+
+/// Get the pixel format with the highest bit depth in `formats`, or `None` if
+/// `formats` is empty.
+///
+/// Formats are ranked with [`GenCamPixelBpp::bit_depth_ord`].
+pub fn max_bit_depth(formats: &[GenCamPixelBpp]) -> Option<GenCamPixelBpp> {
+    formats.iter().copied().max_by(|a, b| a.bit_depth_ord(b))
+}
+
+/// Get the number of channels of a pixel in the color space `color`, or `None` for
+/// [`ColorSpace::Custom`] color spaces.
+///
+/// Bayer mosaic images have a single channel.
+pub fn channels_for(color: &ColorSpace) -> Option<usize> {
+    match color {
+        ColorSpace::Gray | ColorSpace::Bayer(_) => Some(1),
+        ColorSpace::GrayAlpha => Some(2),
+        ColorSpace::Rgb => Some(3),
+        ColorSpace::Rgba => Some(4),
+        _ => None,
+    }
+}
+
+/// Check that an image buffer of `len` bytes holds exactly a `width` x `height` image
+/// in the color space `color`, with elements of [`GenCamPixelBpp::element_bytes`].
+///
+/// # Errors
+/// - [`GenCamError::BufferTooSmall`] with the required length, if the buffer is too small.
+/// - [`GenCamError::InvalidSize`] with `len`, if the buffer is too large.
+/// - [`GenCamError::InvalidImageType`] if the number of channels of `color` is not known.
+pub fn validate_buffer_len(
+    len: usize,
+    width: usize,
+    height: usize,
+    color: &ColorSpace,
+    bpp: GenCamPixelBpp,
+) -> GenCamResult<()> {
+    let channels = channels_for(color).ok_or_else(|| {
+        GenCamError::InvalidImageType(format!("Unknown number of channels: {color:?}"))
+    })?;
+    let required = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(channels * bpp.element_bytes()))
+        .ok_or(GenCamError::InvalidSize(len))?;
+    match len.cmp(&required) {
+        std::cmp::Ordering::Less => Err(GenCamError::BufferTooSmall(required)),
+        std::cmp::Ordering::Greater => Err(GenCamError::InvalidSize(len)),
+        std::cmp::Ordering::Equal => Ok(()),
+    }
+}
+
+/// Parse a GenICam style pixel format name into the bit depth of each channel and the
+/// color space, e.g. `"BayerRG12"` into [`GenCamPixelBpp::Bpp12`] and
+/// [`ColorSpace::Bayer`] with [`refimage::BayerPattern::Rggb`].
+///
+/// The recognized names are a prefix followed by a bit depth of 8, 10, 12 or 16, and an
+/// optional `p` or `Packed` suffix for packed formats, e.g. `"Mono12p"`. The prefix is
+/// matched case-insensitively, and is one of:
+/// - `Mono`, for [`ColorSpace::Gray`].
+/// - `BayerRG`, `BayerBG`, `BayerGR` and `BayerGB`, for [`ColorSpace::Bayer`].
+/// - `RGB` and `BGR`, for [`ColorSpace::Rgb`].
+/// - `RGBa` and `BGRa`, for [`ColorSpace::Rgba`].
+///
+/// The blue-first formats report the color space of their red-first counterparts, so the
+/// driver has to swap the channels. Other names, e.g. YUV formats, yield `None`.
+pub fn parse_pixel_format(name: &str) -> Option<(GenCamPixelBpp, ColorSpace)> {
+    let (kind, rest) = name.split_at(name.find(|c: char| c.is_ascii_digit())?);
+    let (bits, suffix) = rest.split_at(
+        rest.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len()),
+    );
+    if !(suffix.is_empty() || suffix == "p" || suffix.eq_ignore_ascii_case("packed")) {
+        return None;
+    }
+    let bpp = match bits.parse().ok()? {
+        bits @ (8 | 10 | 12 | 16) => GenCamPixelBpp::try_from_bits(bits)?,
+        _ => return None,
+    };
+    let color = match kind.to_ascii_lowercase().as_str() {
+        "mono" => ColorSpace::Gray,
+        "bayerrg" => ColorSpace::Bayer(refimage::BayerPattern::Rggb),
+        "bayerbg" => ColorSpace::Bayer(refimage::BayerPattern::Bggr),
+        "bayergr" => ColorSpace::Bayer(refimage::BayerPattern::Grbg),
+        "bayergb" => ColorSpace::Bayer(refimage::BayerPattern::Gbrg),
+        "rgb" | "bgr" => ColorSpace::Rgb,
+        "rgba" | "bgra" => ColorSpace::Rgba,
+        _ => return None,
+    };
+    Some((bpp, color))
+}
+
 impl From<u32> for GenCamPixelBpp {
     /// Convert from `u32` to [`GenCamPixelBpp`].
     ///
@@ -272,15 +2189,7 @@ impl From<u32> for GenCamPixelBpp {
     /// # Returns
     /// The corresponding [`GenCamPixelBpp`] value.
     fn from(value: u32) -> Self {
-        match value {
-            8 => GenCamPixelBpp::Bpp8,
-            10 => GenCamPixelBpp::Bpp10,
-            12 => GenCamPixelBpp::Bpp12,
-            16 => GenCamPixelBpp::Bpp16,
-            24 => GenCamPixelBpp::Bpp24,
-            32 => GenCamPixelBpp::Bpp32,
-            _ => GenCamPixelBpp::Bpp8,
-        }
+        GenCamPixelBpp::try_from_bits(value).unwrap_or(GenCamPixelBpp::Bpp8)
     }
 }
 
@@ -353,7 +2262,14 @@ pub enum GenCamError {
     /// Exposure not started.
     #[error("Exposure not started.")]
     ExposureNotStarted,
+    /// Exposure cancelled, e.g. with [`GenCam::cancel_capture`], before it completed.
+    #[error("Exposure cancelled")]
+    Cancelled,
     /// Property related error.
+    ///
+    /// Prefer this variant whenever the control is known. A [`PropertyError`] without
+    /// a control can be converted with `?` or [`From`], which yields
+    /// [`GenCamError::InvalidValue`] carrying the error message.
     #[error("Property error: {control:?} - {error:?}")]
     PropertyError {
         /// The control that caused the error.
@@ -361,4 +2277,956 @@ pub enum GenCamError {
         /// The error message.
         error: PropertyError,
     },
+    /// The operation is not implemented by the camera or driver, e.g. an optional
+    /// [`GenCam`] method.
+    #[error("Not implemented: {operation}")]
+    NotImplemented {
+        /// The name of the operation, e.g. `"start_stream"`.
+        operation: String,
+    },
+    /// The camera info ([`GenCamDescriptor::info`]) has no entry with this key.
+    #[error("Info field not found: {0}")]
+    InfoFieldNotFound(String),
+}
+
+impl From<PropertyError> for GenCamError {
+    /// Convert a [`PropertyError`] that is not tied to a control into
+    /// [`GenCamError::InvalidValue`]. Use [`GenCamError::PropertyError`] when the
+    /// control is known.
+    fn from(error: PropertyError) -> Self {
+        GenCamError::InvalidValue(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config_diff() {
+        let exposure = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let gain = GenCamCtrl::Analog(AnalogCtrl::Gain);
+        let offset = GenCamCtrl::Analog(AnalogCtrl::BlackLevel);
+        let temp = GenCamCtrl::Device(DeviceCtrl::Temperature);
+        let mut old = CameraConfig::default();
+        old.values
+            .insert(exposure, (Duration::from_secs(1).into(), false));
+        old.values.insert(gain, (1.0.into(), false));
+        old.values.insert(offset, (10i64.into(), false));
+        old.values.insert(temp, ((-10.0).into(), false));
+        let mut new = old.clone();
+        new.values
+            .insert(exposure, (Duration::from_millis(10).into(), false));
+        new.values.insert(gain, (2.0.into(), true));
+        new.values.remove(&temp);
+
+        let mut diff = old.diff(&new);
+        diff.sort_by_key(|(ctrl, ..)| *ctrl == gain);
+        assert_eq!(
+            diff,
+            vec![
+                (
+                    exposure,
+                    Duration::from_secs(1).into(),
+                    Duration::from_millis(10).into()
+                ),
+                (gain, 1.0.into(), 2.0.into()),
+            ]
+        );
+        assert_eq!(old.removed(&new), vec![temp]);
+        assert_eq!(new.added(&old), vec![temp]);
+        assert!(old.added(&new).is_empty());
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn same_device() {
+        let mut desc = GenCamDescriptor {
+            id: 1,
+            name: "Camera".into(),
+            vendor: "Vendor".into(),
+            info: HashMap::new(),
+        };
+        let mut other = desc.clone();
+        other
+            .info
+            .insert("Temperature".into(), PropertyValue::Float(-10.0));
+        assert_ne!(desc, other);
+        assert!(desc.same_device(&other));
+        assert!(DeviceChanges::between(&[desc.clone()], &[other.clone()]).is_empty());
+
+        other.id = 2;
+        assert!(!desc.same_device(&other));
+        // the serial number takes precedence over the ID
+        let serial = GenCamDescriptor::SERIAL_NUMBER.to_string();
+        desc.info.insert(serial.clone(), "A123".to_string().into());
+        other.info.insert(serial.clone(), "A123".to_string().into());
+        assert!(desc.same_device(&other));
+        other.info.insert(serial, "B456".to_string().into());
+        other.id = 1;
+        assert!(!desc.same_device(&other));
+        other.info.clear();
+        assert!(desc.same_device(&other));
+        other.name = "Other Camera".into();
+        assert!(!desc.same_device(&other));
+    }
+
+    #[test]
+    fn trigger_mode() {
+        assert_eq!(TriggerMode::from("software"), TriggerMode::Software);
+        assert_eq!(TriggerMode::from("Soft Trigger"), TriggerMode::Software);
+        assert_eq!(TriggerMode::from("OFF"), TriggerMode::Off);
+        assert_eq!(
+            TriggerMode::from("Edge_Rising"),
+            TriggerMode::HardwareRising
+        );
+        assert_eq!(
+            TriggerMode::from("falling-edge"),
+            TriggerMode::HardwareFalling
+        );
+        assert_eq!(
+            TriggerMode::from("Level High"),
+            TriggerMode::Other("Level High".into())
+        );
+        assert!(TriggerMode::Software.matches("SOFTWARE"));
+        assert!(TriggerMode::Other("Level High".into()).matches("level high"));
+        assert!(!TriggerMode::Other("Level High".into()).matches("Level Low"));
+        assert!(!TriggerMode::Off.matches("Software"));
+    }
+
+    /// A camera with the given properties, recording the values written to it.
+    #[derive(Debug, Default)]
+    struct StubCam {
+        props: HashMap<GenCamCtrl, Property>,
+        written: Vec<(GenCamCtrl, PropertyValue)>,
+        roi: GenCamRoi,
+    }
+
+    impl GenCam for StubCam {
+        fn info_handle(&self) -> Option<AnyGenCamInfo> {
+            None
+        }
+        fn info(&self) -> GenCamResult<&GenCamDescriptor> {
+            Err(GenCamError::NotImplemented {
+                operation: "info".into(),
+            })
+        }
+        fn vendor(&self) -> &str {
+            "Stub"
+        }
+        fn camera_ready(&self) -> bool {
+            true
+        }
+        fn camera_name(&self) -> &str {
+            "Stub Camera"
+        }
+        fn list_properties(&self) -> &HashMap<GenCamCtrl, Property> {
+            &self.props
+        }
+        fn get_property(&self, name: GenCamCtrl) -> GenCamResult<(PropertyValue, bool)> {
+            self.written
+                .iter()
+                .rev()
+                .find(|(ctrl, _)| *ctrl == name)
+                .map(|(_, value)| (value.clone(), false))
+                .ok_or(GenCamError::PropertyError {
+                    control: name,
+                    error: PropertyError::NotFound,
+                })
+        }
+        fn set_property(&mut self, name: GenCamCtrl, value: &PropertyValue) -> GenCamResult<()> {
+            self.written.push((name, value.clone()));
+            Ok(())
+        }
+        fn set_property_auto(
+            &mut self,
+            name: GenCamCtrl,
+            value: &PropertyValue,
+        ) -> GenCamResult<()> {
+            self.set_property(name, value)
+        }
+        fn cancel_capture(&self) -> GenCamResult<()> {
+            Err(GenCamError::ExposureNotStarted)
+        }
+        fn is_capturing(&self) -> bool {
+            false
+        }
+        fn start_exposure(&mut self) -> GenCamResult<()> {
+            Err(GenCamError::NotImplemented {
+                operation: "start_exposure".into(),
+            })
+        }
+        fn poll_exposure(&mut self) -> PollExposure<'_> {
+            PollExposure::Ready(Err(GenCamError::ExposureNotStarted))
+        }
+        fn camera_state(&self) -> GenCamResult<GenCamState> {
+            Ok(GenCamState::Idle)
+        }
+        fn set_roi(&mut self, roi: &GenCamRoi) -> GenCamResult<&GenCamRoi> {
+            self.roi = *roi;
+            Ok(&self.roi)
+        }
+        fn get_roi(&self) -> &GenCamRoi {
+            &self.roi
+        }
+    }
+
+    #[test]
+    fn not_implemented() {
+        let mut cam = StubCam::default();
+        let operation = |res: GenCamResult<()>| match res {
+            Err(GenCamError::NotImplemented { operation }) => operation,
+            res => panic!("unexpected result {res:?}"),
+        };
+        assert_eq!(operation(cam.start_stream().map(drop)), "start_stream");
+        assert_eq!(
+            operation(cam.last_frame_meta().map(drop)),
+            "last_frame_meta"
+        );
+        assert_eq!(operation(cam.reconnect()), "reconnect");
+        assert_eq!(
+            GenCamError::NotImplemented {
+                operation: "reconnect".into()
+            }
+            .to_string(),
+            "Not implemented: reconnect"
+        );
+        // missing controls are reported as such, not as a `GenCamError::Message`
+        let not_found = |ctrl| GenCamError::PropertyError {
+            control: ctrl,
+            error: PropertyError::NotFound,
+        };
+        assert_eq!(
+            cam.sensor_size().unwrap_err(),
+            not_found(GenCamCtrl::Sensor(SensorCtrl::WidthMax))
+        );
+        assert_eq!(
+            cam.temperature().unwrap_err(),
+            not_found(GenCamCtrl::Device(DeviceCtrl::Temperature))
+        );
+        assert_eq!(
+            cam.exposure_limits().unwrap_err(),
+            not_found(GenCamCtrl::Exposure(ExposureCtrl::ExposureTime))
+        );
+    }
+
+    #[test]
+    fn capabilities_from_properties() {
+        let mut cam = StubCam::default();
+        assert_eq!(cam.capabilities(), Capabilities::default());
+        let bool_prop = Property::new(
+            property::PropertyLims::Bool { default: false },
+            false,
+            false,
+        );
+        cam.props.insert(
+            GenCamCtrl::Trigger(TriggerCtrl::Mod),
+            Property::new(
+                property::PropertyLims::EnumStr {
+                    variants: vec!["Normal".into(), "Rising Edge".into()],
+                    default: "Normal".into(),
+                },
+                false,
+                false,
+            ),
+        );
+        cam.props.insert(
+            GenCamCtrl::Device(DeviceCtrl::CoolerEnable),
+            bool_prop.clone(),
+        );
+        cam.props
+            .insert(GenCamCtrl::Sensor(SensorCtrl::BinningBoth), bool_prop);
+        let caps = cam.capabilities();
+        assert!(caps.hardware_trigger && caps.cooling && caps.binning);
+        assert!(!caps.software_trigger && !caps.exposure && !caps.streaming);
+    }
+
+    #[test]
+    fn white_balance() {
+        let mut cam = StubCam::default();
+        assert!(matches!(
+            cam.set_white_balance(1.0, 1.0, 1.0),
+            Err(GenCamError::NotImplemented { .. })
+        ));
+
+        let (sel, ratio) = (
+            GenCamCtrl::Analog(AnalogCtrl::BalanceRatioSel),
+            GenCamCtrl::Analog(AnalogCtrl::BalanceRatio),
+        );
+        cam.props.insert(
+            sel,
+            Property::new(
+                property::PropertyLims::EnumStr {
+                    variants: vec!["Blue".into(), "Green".into(), "Red".into()],
+                    default: "Green".into(),
+                },
+                false,
+                false,
+            ),
+        );
+        cam.props.insert(
+            ratio,
+            Property::new(
+                property::PropertyLims::Float {
+                    min: 0.0,
+                    max: 4.0,
+                    step: 0.01,
+                    default: 1.0,
+                },
+                false,
+                false,
+            ),
+        );
+        let channel = |name: &str| (sel, PropertyValue::EnumStr(name.into()));
+        cam.written.push(channel("Green"));
+        cam.set_white_balance(1.5, 1.0, 2.25).unwrap();
+        assert_eq!(
+            cam.written[1..],
+            [
+                channel("Red"),
+                (ratio, PropertyValue::Float(1.5)),
+                channel("Green"),
+                (ratio, PropertyValue::Float(1.0)),
+                channel("Blue"),
+                (ratio, PropertyValue::Float(2.25)),
+                channel("Green"),
+            ]
+        );
+        // the stub does not keep a ratio per channel
+        assert_eq!(cam.get_white_balance(), Ok((2.25, 2.25, 2.25)));
+        assert_eq!(cam.written.last(), Some(&channel("Green")));
+
+        // an invalid ratio is rejected before anything is written
+        let written = cam.written.len();
+        assert_eq!(
+            cam.set_white_balance(1.0, 1.0, 5.0),
+            Err(GenCamError::PropertyError {
+                control: ratio,
+                error: PropertyError::ValueOutOfRange {
+                    min: 0.0.into(),
+                    max: 4.0.into(),
+                    value: 5.0.into(),
+                },
+            })
+        );
+        assert_eq!(cam.written.len(), written);
+    }
+
+    #[test]
+    fn software_trigger() {
+        let mut cam = StubCam::default();
+        assert_eq!(
+            cam.trigger_now(),
+            Err(GenCamError::NotImplemented {
+                operation: "trigger_now".into()
+            })
+        );
+        assert!(cam.written.is_empty());
+
+        let mode = GenCamCtrl::Trigger(TriggerCtrl::Mod);
+        let fire = GenCamCtrl::Trigger(TriggerCtrl::SOFTWARE);
+        cam.props.insert(
+            mode,
+            Property::new(
+                property::PropertyLims::EnumStr {
+                    variants: vec!["Normal".into(), "Soft Trigger".into()],
+                    default: "Normal".into(),
+                },
+                false,
+                false,
+            ),
+        );
+        cam.props.insert(
+            fire,
+            // there are no command limits, the value written is what matters
+            Property::new(
+                property::PropertyLims::Bool { default: false },
+                false,
+                false,
+            ),
+        );
+        cam.set_trigger_mode(TriggerMode::Software).unwrap();
+        assert_eq!(cam.get_trigger_mode(), Ok(TriggerMode::Software));
+        assert_eq!(
+            cam.set_trigger_mode(TriggerMode::HardwareFalling),
+            Err(GenCamError::PropertyError {
+                control: mode,
+                error: PropertyError::ValueNotSupported,
+            })
+        );
+        cam.trigger_now().unwrap();
+        assert_eq!(cam.written.last(), Some(&(fire, PropertyValue::Command)));
+    }
+
+    #[test]
+    fn pixel_format_names() {
+        use GenCamPixelBpp::*;
+        use refimage::BayerPattern;
+        let table = [
+            ("Mono8", Some((Bpp8, ColorSpace::Gray))),
+            ("Mono10", Some((Bpp10, ColorSpace::Gray))),
+            ("Mono12Packed", Some((Bpp12, ColorSpace::Gray))),
+            ("Mono12p", Some((Bpp12, ColorSpace::Gray))),
+            ("mono16", Some((Bpp16, ColorSpace::Gray))),
+            (
+                "BayerRG8",
+                Some((Bpp8, ColorSpace::Bayer(BayerPattern::Rggb))),
+            ),
+            (
+                "BayerBG10p",
+                Some((Bpp10, ColorSpace::Bayer(BayerPattern::Bggr))),
+            ),
+            (
+                "BayerGR12",
+                Some((Bpp12, ColorSpace::Bayer(BayerPattern::Grbg))),
+            ),
+            (
+                "BayerGB16",
+                Some((Bpp16, ColorSpace::Bayer(BayerPattern::Gbrg))),
+            ),
+            ("RGB8", Some((Bpp8, ColorSpace::Rgb))),
+            ("BGR8", Some((Bpp8, ColorSpace::Rgb))),
+            ("RGBa8", Some((Bpp8, ColorSpace::Rgba))),
+            ("BGRa16", Some((Bpp16, ColorSpace::Rgba))),
+            ("YUV422_8", None),
+            ("Mono24", None),
+            ("Mono8Signed", None),
+            ("Mono", None),
+            ("", None),
+        ];
+        for (name, expected) in table {
+            assert_eq!(parse_pixel_format(name), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn current_bpp() {
+        let mut cam = StubCam::default();
+        let ctrl = GenCamCtrl::Sensor(SensorCtrl::PixelFormat);
+        assert!(matches!(
+            cam.current_bpp(),
+            Err(GenCamError::PropertyError {
+                error: PropertyError::NotFound,
+                ..
+            })
+        ));
+        cam.props.insert(
+            ctrl,
+            Property::new(
+                property::PropertyLims::EnumStr {
+                    variants: vec!["Mono12".into(), "RGB8".into(), "YUV422".into()],
+                    default: "Mono12".into(),
+                },
+                false,
+                false,
+            ),
+        );
+        cam.set_property(ctrl, &PropertyValue::EnumStr("Mono12".into()))
+            .unwrap();
+        assert_eq!(cam.current_bpp(), Ok(GenCamPixelBpp::Bpp12));
+        cam.set_property(ctrl, &PropertyValue::EnumStr("RGB8".into()))
+            .unwrap();
+        assert_eq!(cam.current_bpp(), Ok(GenCamPixelBpp::Bpp8));
+        cam.set_property(ctrl, &PropertyValue::EnumStr("Custom".into()))
+            .unwrap();
+        assert!(matches!(
+            cam.current_bpp(),
+            Err(GenCamError::InvalidFormat(_))
+        ));
+        cam.set_property(ctrl, &GenCamPixelBpp::Bpp16.into())
+            .unwrap();
+        assert_eq!(cam.current_bpp(), Ok(GenCamPixelBpp::Bpp16));
+        // color formats report the depth of each channel, like their names
+        for bpp in [GenCamPixelBpp::Bpp24, GenCamPixelBpp::Bpp32] {
+            cam.set_property(ctrl, &bpp.into()).unwrap();
+            assert_eq!(cam.current_bpp(), Ok(GenCamPixelBpp::Bpp8));
+        }
+    }
+
+    #[test]
+    fn mono_buffer_len() {
+        let mut cam = StubCam {
+            roi: GenCamRoi {
+                x_min: 0,
+                y_min: 0,
+                width: 64,
+                height: 48,
+            },
+            ..Default::default()
+        };
+        assert_eq!(cam.color_space(), Ok(ColorSpace::Gray));
+        assert_eq!(cam.frame_buffer_len(), Ok(64 * 48));
+
+        let ctrl = GenCamCtrl::Sensor(SensorCtrl::PixelFormat);
+        cam.props.insert(
+            ctrl,
+            Property::new(
+                property::PropertyLims::PixelFmt {
+                    variants: vec![GenCamPixelBpp::Bpp8, GenCamPixelBpp::Bpp12],
+                    default: GenCamPixelBpp::Bpp8,
+                },
+                false,
+                false,
+            ),
+        );
+        cam.set_property(ctrl, &GenCamPixelBpp::Bpp12.into())
+            .unwrap();
+        assert_eq!(cam.frame_buffer_len(), Ok(64 * 48 * 2));
+        assert_eq!(
+            validate_buffer_len(
+                64 * 48 * 2,
+                64,
+                48,
+                &ColorSpace::Gray,
+                GenCamPixelBpp::Bpp12
+            ),
+            Ok(())
+        );
+
+        // the pixel format may also be reported by name
+        cam.props.insert(
+            ctrl,
+            Property::new(
+                property::PropertyLims::EnumStr {
+                    variants: vec!["Mono8".into(), "Mono16".into()],
+                    default: "Mono8".into(),
+                },
+                false,
+                false,
+            ),
+        );
+        cam.set_property(ctrl, &PropertyValue::EnumStr("Mono16".into()))
+            .unwrap();
+        assert_eq!(cam.frame_buffer_len(), Ok(64 * 48 * 2));
+        cam.set_property(ctrl, &PropertyValue::EnumStr("Mono8".into()))
+            .unwrap();
+        assert_eq!(cam.frame_buffer_len(), Ok(64 * 48));
+    }
+
+    #[test]
+    fn reset() {
+        let mut cam = StubCam::default();
+        cam.reset().unwrap();
+        assert!(cam.written.is_empty());
+
+        let ctrl = GenCamCtrl::Device(DeviceCtrl::Reset);
+        cam.props.insert(
+            ctrl,
+            Property::new(
+                property::PropertyLims::Bool { default: false },
+                false,
+                false,
+            ),
+        );
+        cam.reset().unwrap();
+        assert_eq!(cam.written, vec![(ctrl, PropertyValue::Command)]);
+    }
+
+    #[test]
+    fn exposure_unit() {
+        assert_eq!(
+            ExposureUnit::Milliseconds.duration_of(2.5),
+            Ok(Duration::from_micros(2500))
+        );
+        assert_eq!(
+            ExposureUnit::Seconds.value_of(Duration::from_millis(1500)),
+            1.5
+        );
+        assert!(ExposureUnit::Seconds.duration_of(-1.0).is_err());
+
+        // a microsecond-based float property
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let mut cam = StubCam::default();
+        cam.props.insert(
+            ctrl,
+            Property::new(
+                property::PropertyLims::Float {
+                    min: 10.0,
+                    max: 1e8,
+                    step: 1.0,
+                    default: 1e6,
+                },
+                false,
+                false,
+            ),
+        );
+        cam.set_exposure(Duration::from_micros(1500)).unwrap();
+        assert!(matches!(
+            cam.written.last(),
+            Some((_, PropertyValue::Float(v))) if *v == 1500.0
+        ));
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_micros(1500)));
+        cam.written.push((ctrl, PropertyValue::Unsigned(250)));
+        assert_eq!(cam.get_exposure(), Ok(Duration::from_micros(250)));
+    }
+
+    #[test]
+    fn gain_db() {
+        assert_eq!(gain_raw_to_db(10.0, 10.0), 0.0);
+        assert!((gain_raw_to_db(20.0, 10.0) - 6.0206).abs() < 1e-4);
+        assert!((gain_raw_to_db(1.0, 10.0) + 20.0).abs() < 1e-9);
+        for (db, reference) in [(0.0, 1.0), (6.0, 1.0), (12.5, 16.0), (-3.0, 2.0)] {
+            let raw = gain_db_to_raw(db, reference);
+            assert!((gain_raw_to_db(raw, reference) - db).abs() < 1e-9);
+        }
+        assert!((gain_db_to_raw(40.0, 1.0) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn set_gain_db() {
+        let ctrl = GenCamCtrl::Analog(AnalogCtrl::Gain);
+        let mut cam = StubCam::default();
+        cam.props.insert(
+            ctrl,
+            Property::new(
+                property::PropertyLims::Unsigned {
+                    min: 1,
+                    max: 100,
+                    step: 1,
+                    default: 1,
+                },
+                false,
+                false,
+            ),
+        );
+        cam.set_gain_db(20.0).unwrap();
+        assert_eq!(
+            cam.get_property(ctrl),
+            Ok((PropertyValue::Unsigned(10), false))
+        );
+        cam.set_gain_db(6.0).unwrap();
+        assert_eq!(
+            cam.get_property(ctrl),
+            Ok((PropertyValue::Unsigned(2), false))
+        );
+        // clamped to the maximum gain
+        cam.set_gain_db(60.0).unwrap();
+        assert_eq!(
+            cam.get_property(ctrl),
+            Ok((PropertyValue::Unsigned(100), false))
+        );
+
+        cam.props.insert(
+            ctrl,
+            Property::new(
+                property::PropertyLims::Float {
+                    min: 0.5,
+                    max: 8.0,
+                    step: 0.0,
+                    default: 0.5,
+                },
+                false,
+                false,
+            ),
+        );
+        cam.set_gain_db(12.0).unwrap();
+        let (value, _) = cam.get_property(ctrl).unwrap();
+        assert!((value.as_f64().unwrap() - gain_db_to_raw(12.0, 0.5)).abs() < 1e-9);
+        assert!(matches!(value, PropertyValue::Float(_)));
+        cam.set_gain_db(-6.0).unwrap();
+        assert_eq!(
+            cam.get_property(ctrl),
+            Ok((PropertyValue::Float(0.5), false))
+        );
+    }
+
+    #[test]
+    fn roi_centered() {
+        assert_eq!(
+            GenCamRoi::centered(101, 51, 1920, 1080),
+            GenCamRoi {
+                x_min: 909,
+                y_min: 514,
+                width: 101,
+                height: 51,
+            }
+        );
+        assert_eq!(
+            GenCamRoi::centered(4000, 100, 1920, 1080),
+            GenCamRoi {
+                x_min: 0,
+                y_min: 490,
+                width: 1920,
+                height: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn roi_from_fractions() {
+        assert_eq!(
+            GenCamRoi::from_fractions(0.25, 0.25, 0.5, 0.5, 1920, 1080),
+            Ok(GenCamRoi::centered(960, 540, 1920, 1080))
+        );
+        assert_eq!(
+            GenCamRoi::from_fractions(0.0, 0.0, 1.0, 1.0, 1920, 1080),
+            Ok(GenCamRoi {
+                x_min: 0,
+                y_min: 0,
+                width: 1920,
+                height: 1080,
+            })
+        );
+        for fracs in [
+            (1.5, 0.0, 0.5, 0.5),
+            (0.0, -0.1, 0.5, 0.5),
+            (0.0, 0.0, f32::NAN, 0.5),
+            (0.75, 0.0, 0.5, 0.5),
+            (0.0, 0.0, 0.0, 0.5),
+        ] {
+            assert!(matches!(
+                GenCamRoi::from_fractions(fracs.0, fracs.1, fracs.2, fracs.3, 1920, 1080),
+                Err(GenCamError::OutOfBounds(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn roi_normalized() {
+        let full = GenCamRoi {
+            x_min: 0,
+            y_min: 0,
+            width: 1920,
+            height: 1080,
+        };
+        assert_ne!(GenCamRoi::default(), full);
+        assert_eq!(GenCamRoi::default().normalized(1920, 1080), full);
+        assert_eq!(full.normalized(1920, 1080), full);
+        let roi = GenCamRoi::centered(100, 100, 1920, 1080);
+        assert_eq!(roi.normalized(1920, 1080), roi);
+    }
+
+    #[test]
+    fn roi_scale_to_binning() {
+        let roi = GenCamRoi {
+            x_min: 101,
+            y_min: 50,
+            width: 640,
+            height: 481,
+        };
+        assert_eq!(
+            roi.scale_to_binning(2),
+            GenCamRoi {
+                x_min: 50,
+                y_min: 25,
+                width: 320,
+                height: 240,
+            }
+        );
+        assert_eq!(roi.scale_to_binning(1), roi);
+        assert_eq!(roi.scale_to_binning(0), roi);
+    }
+
+    #[test]
+    fn roi_align_to() {
+        let grid = RoiConstraints {
+            x_step: 4,
+            y_step: 4,
+            width_step: 4,
+            height_step: 4,
+            min_width: 8,
+            min_height: 8,
+        };
+        let roi = GenCamRoi {
+            x_min: 101,
+            y_min: 7,
+            width: 63,
+            height: 5,
+        };
+        assert_eq!(
+            roi.align_to(&grid),
+            GenCamRoi {
+                x_min: 100,
+                y_min: 4,
+                width: 60,
+                height: 8,
+            }
+        );
+        assert_eq!(roi.align_to(&RoiConstraints::default()), roi);
+        // a minimum size off the grid is rounded up, and zero steps are ignored
+        let odd = RoiConstraints {
+            x_step: 0,
+            min_width: 10,
+            width_step: 4,
+            ..Default::default()
+        };
+        assert_eq!(roi.align_to(&odd).x_min, 101);
+        assert_eq!(roi.align_to(&odd).width, 60);
+        assert_eq!(GenCamRoi { width: 3, ..roi }.align_to(&odd).width, 12);
+    }
+
+    #[test]
+    fn roi_geometry() {
+        let roi = |x_min, y_min, width, height| GenCamRoi {
+            x_min,
+            y_min,
+            width,
+            height,
+        };
+        let outer = roi(10, 10, 100, 50);
+        assert!(outer.contains(10, 10));
+        assert!(outer.contains(109, 59));
+        assert!(!outer.contains(110, 30));
+        assert!(!outer.contains(9, 30));
+
+        // touching along an edge
+        let right = roi(110, 10, 20, 20);
+        assert!(!outer.intersects(&right));
+        assert_eq!(outer.intersection(&right), None);
+        let below = roi(10, 60, 100, 1);
+        assert!(!outer.intersects(&below));
+
+        // fully contained
+        let inner = roi(20, 20, 10, 10);
+        assert!(outer.intersects(&inner));
+        assert_eq!(outer.intersection(&inner), Some(inner));
+        assert_eq!(inner.intersection(&outer), Some(inner));
+
+        // partial overlap
+        assert_eq!(
+            outer.intersection(&roi(100, 0, 20, 20)),
+            Some(roi(100, 10, 10, 10))
+        );
+
+        // empty regions contain nothing
+        let empty = GenCamRoi::default();
+        assert!(!empty.contains(0, 0));
+        assert!(!empty.intersects(&outer));
+    }
+
+    #[test]
+    fn property_error_conversion() {
+        fn check(value: &PropertyValue) -> GenCamResult<u64> {
+            Ok(value.try_into()?)
+        }
+        assert_eq!(check(&PropertyValue::Unsigned(3)), Ok(3));
+        assert_eq!(
+            check(&PropertyValue::Bool(true)),
+            Err(GenCamError::InvalidValue(
+                PropertyError::InvalidControlType {
+                    expected: PropertyType::Unsigned,
+                    received: PropertyType::Bool,
+                }
+                .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn roi_validation() {
+        let full = GenCamRoi {
+            x_min: 0,
+            y_min: 0,
+            width: 1920,
+            height: 1080,
+        };
+        assert_eq!(validate_roi(&GenCamRoi::default(), 1920, 1080), Ok(full));
+        assert_eq!(validate_roi(&full, 1920, 1080), Ok(full));
+        let overflow = GenCamRoi { x_min: 1, ..full };
+        assert!(matches!(
+            validate_roi(&overflow, 1920, 1080),
+            Err(GenCamError::OutOfBounds(_))
+        ));
+        let overflow = GenCamRoi {
+            y_min: 81,
+            height: 1000,
+            ..full
+        };
+        assert!(matches!(
+            validate_roi(&overflow, 1920, 1080),
+            Err(GenCamError::OutOfBounds(_))
+        ));
+        let zero_width = GenCamRoi { width: 0, ..full };
+        assert!(matches!(
+            validate_roi(&zero_width, 1920, 1080),
+            Err(GenCamError::OutOfBounds(_))
+        ));
+    }
+
+    #[test]
+    fn state_predicates() {
+        let states = [
+            GenCamState::Idle,
+            GenCamState::Exposing(Some(Duration::from_millis(10))),
+            GenCamState::ExposureFinished,
+            GenCamState::Downloading(Some(50)),
+            GenCamState::Errored(GenCamError::TimedOut),
+            GenCamState::Unknown,
+        ];
+        let idle: Vec<_> = states.iter().map(|s| s.is_idle()).collect();
+        assert_eq!(idle, [true, false, false, false, false, false]);
+        let exposing: Vec<_> = states.iter().map(|s| s.is_exposing()).collect();
+        assert_eq!(exposing, [false, true, false, false, false, false]);
+        let finished: Vec<_> = states.iter().map(|s| s.is_finished()).collect();
+        assert_eq!(finished, [false, false, true, false, false, false]);
+        let error: Vec<_> = states.iter().map(|s| s.is_error()).collect();
+        assert_eq!(error, [false, false, false, false, true, false]);
+    }
+
+    #[test]
+    fn state_progress() {
+        assert_eq!(GenCamState::Idle.progress(), None);
+        assert_eq!(GenCamState::Exposing(None).progress(), None);
+        assert_eq!(
+            GenCamState::Exposing(Some(Duration::from_secs(1))).progress(),
+            None
+        );
+        assert_eq!(GenCamState::ExposureFinished.progress(), Some(1.0));
+        assert_eq!(GenCamState::Downloading(Some(50)).progress(), Some(0.5));
+        assert_eq!(GenCamState::Downloading(None).progress(), None);
+    }
+
+    #[test]
+    fn max_bit_depth() {
+        use GenCamPixelBpp::*;
+        assert_eq!(
+            super::max_bit_depth(&[Bpp24, Bpp8, Bpp32, Bpp16]),
+            Some(Bpp32)
+        );
+        assert_eq!(super::max_bit_depth(&[Bpp12, Bpp10]), Some(Bpp12));
+        assert_eq!(super::max_bit_depth(&[]), None);
+        assert_eq!(Bpp24.bit_depth_ord(&Bpp32), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn buffer_len() {
+        assert_eq!(GenCamPixelBpp::Bpp16.element_bytes(), 2);
+        assert_eq!(GenCamPixelBpp::Bpp24.element_bytes(), 1);
+        assert_eq!(channels_for(&ColorSpace::Rgb), Some(3));
+        assert_eq!(
+            channels_for(&ColorSpace::Custom("CMY".into(), "".into())),
+            None
+        );
+
+        // 16-bit RGB
+        let rgb = |len| validate_buffer_len(len, 4, 3, &ColorSpace::Rgb, GenCamPixelBpp::Bpp16);
+        assert_eq!(rgb(72), Ok(()));
+        assert_eq!(rgb(71), Err(GenCamError::BufferTooSmall(72)));
+        assert_eq!(rgb(80), Err(GenCamError::InvalidSize(80)));
+        // 8-bit mono
+        let mono =
+            |len| validate_buffer_len(len, 640, 480, &ColorSpace::Gray, GenCamPixelBpp::Bpp8);
+        assert_eq!(mono(640 * 480), Ok(()));
+        assert_eq!(
+            mono(640 * 480 * 2),
+            Err(GenCamError::InvalidSize(640 * 480 * 2))
+        );
+        assert_eq!(mono(0), Err(GenCamError::BufferTooSmall(640 * 480)));
+    }
+
+    #[test]
+    fn pixel_bpp_bytes() {
+        assert_eq!(GenCamPixelBpp::Bpp8.bytes_per_pixel(), 1);
+        assert_eq!(GenCamPixelBpp::Bpp10.bytes_per_pixel(), 2);
+        assert_eq!(GenCamPixelBpp::Bpp12.bytes_per_pixel(), 2);
+        assert_eq!(GenCamPixelBpp::Bpp16.bytes_per_pixel(), 2);
+        assert_eq!(GenCamPixelBpp::Bpp24.bytes_per_pixel(), 3);
+        assert_eq!(GenCamPixelBpp::Bpp12.bits(), 12);
+        assert_eq!(GenCamPixelBpp::try_from_bits(14), None);
+        assert_eq!(GenCamPixelBpp::from(14), GenCamPixelBpp::Bpp8);
+        assert_eq!(
+            GenCamPixelBpp::try_from_bits(10),
+            Some(GenCamPixelBpp::Bpp10)
+        );
+    }
 }
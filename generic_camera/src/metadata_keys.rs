@@ -0,0 +1,24 @@
+/*!
+ * # Metadata Keys
+ * Standard image metadata keys, following the FITS header conventions.
+ *
+ * Drivers should use these constants when calling `insert_key` on a captured image,
+ * so that the same setting is recorded under the same key by all cameras.
+ */
+
+/// The X offset of the region of interest, in binned pixels.
+pub const X_OFFSET: &str = "XOFST";
+/// The Y offset of the region of interest, in binned pixels.
+pub const Y_OFFSET: &str = "YOFST";
+/// The exposure time, in seconds.
+pub const EXPOSURE_TIME: &str = "EXPTIME";
+/// The gain.
+pub const GAIN: &str = "GAIN";
+/// The sensor temperature, in degrees Celsius.
+pub const CCD_TEMP: &str = "CCD-TEMP";
+/// The horizontal binning factor.
+pub const BINNING_X: &str = "XBINNING";
+/// The vertical binning factor.
+pub const BINNING_Y: &str = "YBINNING";
+/// The camera (instrument) name.
+pub const INSTRUMENT: &str = "INSTRUME";
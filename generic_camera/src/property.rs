@@ -31,6 +31,41 @@ impl Property {
         }
     }
 
+    /// Create a writable exposure time property from `min` to `max`, with a step of 1 µs
+    /// and a default of 1 s (clamped to the limits), without auto mode.
+    pub fn exposure_duration(min: Duration, max: Duration) -> Self {
+        Property::new(
+            PropertyLims::Duration {
+                min,
+                max,
+                step: Duration::from_micros(1),
+                default: Duration::from_secs(1).clamp(min, max.max(min)),
+            },
+            false,
+            false,
+        )
+    }
+
+    /// Create a writable percentage property from 0.0 to 100.0, with a step of 1.0 and a
+    /// default of 0.0, without auto mode.
+    pub fn percent_float() -> Self {
+        Property::new(
+            PropertyLims::Float {
+                min: 0.0,
+                max: 100.0,
+                step: 1.0,
+                default: 0.0,
+            },
+            false,
+            false,
+        )
+    }
+
+    /// Create a writable boolean property, without auto mode.
+    pub fn boolean(default: bool) -> Self {
+        Property::new(PropertyLims::Bool { default }, false, false)
+    }
+
     /// Set an optional documentation string
     pub fn set_doc<T: Into<String>>(&mut self, doc: T) {
         self.doc = Some(doc.into());
@@ -46,11 +81,64 @@ impl Property {
         (&self.prop).into()
     }
 
+    /// Check if the property is enum-like, i.e. takes one of a list of variants
+    /// ([`PropertyType::EnumStr`], [`PropertyType::EnumInt`], [`PropertyType::EnumUnsigned`]
+    /// or [`PropertyType::PixelFmt`]).
+    pub fn is_enum(&self) -> bool {
+        self.variant_kind().is_some()
+    }
+
+    /// Get the precise type of the variants of an enum-like property, or `None` if the
+    /// property is not enum-like.
+    ///
+    /// Values of [`PropertyType::EnumInt`] and [`PropertyType::EnumUnsigned`] properties are
+    /// represented by [`PropertyValue::Int`] and [`PropertyValue::Unsigned`], so this can be
+    /// used to tell an enumerated choice apart from a free integer.
+    pub fn variant_kind(&self) -> Option<PropertyType> {
+        use PropertyLims::*;
+        match &self.prop {
+            PixelFmt { .. } | EnumStr { .. } | EnumInt { .. } | EnumUnsigned { .. } => {
+                Some(self.get_type())
+            }
+            Bool { .. } | Int { .. } | Float { .. } | Unsigned { .. } | Duration { .. } => None,
+        }
+    }
+
     /// Check if the property supports auto mode
     pub fn supports_auto(&self) -> bool {
         self.auto
     }
 
+    /// Check if the property is read-only
+    pub fn is_readonly(&self) -> bool {
+        self.rdonly
+    }
+
+    /// Check if the property can be written, returning [`PropertyError::ReadOnly`] if not.
+    ///
+    /// Drivers should call this together with [`Property::validate`] before writing a value.
+    pub fn check_writable(&self) -> PropertyResult<()> {
+        if self.rdonly {
+            Err(PropertyError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Validate a write of `value` to the property, in auto mode if `auto` is set.
+    ///
+    /// This combines [`Property::validate`] and [`Property::check_writable`], and
+    /// additionally returns [`PropertyError::AutoNotSupported`] if auto mode is
+    /// requested but not supported.
+    pub fn validate_write(&self, value: &PropertyValue, auto: bool) -> PropertyResult<()> {
+        self.validate(value)?;
+        self.check_writable()?;
+        if auto && !self.auto {
+            return Err(PropertyError::AutoNotSupported);
+        }
+        Ok(())
+    }
+
     /// Validate a property value
     pub fn validate(&self, value: &PropertyValue) -> PropertyResult<()> {
         // 1. Check if value in enum
@@ -152,7 +240,14 @@ impl Property {
             | PropertyType::Unsigned
             | PropertyType::Float
             | PropertyType::Duration => {
-                if &self.get_min()? <= value && value <= &self.get_max()? {
+                let (min, max) = (self.get_min()?, self.get_max()?);
+                if min
+                    .numeric_cmp(value)
+                    .is_some_and(std::cmp::Ordering::is_le)
+                    && value
+                        .numeric_cmp(&max)
+                        .is_some_and(std::cmp::Ordering::is_le)
+                {
                     Ok(())
                 } else {
                     Err(PropertyError::ValueOutOfRange {
@@ -171,6 +266,88 @@ impl Property {
         }
     }
 
+    /// Make a value of the type of this property from an integer (e.g. the position of
+    /// a UI slider), and validate it against the limits of the property.
+    ///
+    /// - [`PropertyType::Int`] and [`PropertyType::EnumInt`] properties take `v` as is.
+    /// - [`PropertyType::Unsigned`] and [`PropertyType::EnumUnsigned`] properties reject
+    ///   negative values.
+    /// - [`PropertyType::Float`] properties take `v` as a float.
+    ///
+    /// # Errors
+    /// - [`PropertyError::NotNumber`] for other properties.
+    /// - [`PropertyError::ValueOutOfRange`] or [`PropertyError::ValueNotSupported`] if the
+    ///   value is not valid for the property, see [`Property::validate`].
+    pub fn make_value_from_i64(&self, v: i64) -> PropertyResult<PropertyValue> {
+        use PropertyLims::*;
+        let value = match &self.prop {
+            Int { .. } | EnumInt { .. } => PropertyValue::Int(v),
+            Float { .. } => PropertyValue::Float(v as f64),
+            Unsigned { .. } => match u64::try_from(v) {
+                Ok(v) => PropertyValue::Unsigned(v),
+                Err(_) => {
+                    return Err(PropertyError::ValueOutOfRange {
+                        min: self.get_min()?,
+                        max: self.get_max()?,
+                        value: PropertyValue::Int(v),
+                    });
+                }
+            },
+            EnumUnsigned { .. } => PropertyValue::Unsigned(
+                u64::try_from(v).map_err(|_| PropertyError::ValueNotSupported)?,
+            ),
+            Bool { .. } | Duration { .. } | PixelFmt { .. } | EnumStr { .. } => {
+                return Err(PropertyError::NotNumber);
+            }
+        };
+        self.validate(&value)?;
+        Ok(value)
+    }
+
+    /// Make a value of the type of this property from a float, like
+    /// [`Property::make_value_from_i64`].
+    ///
+    /// For properties taking integers, `v` is rounded to the nearest integer.
+    ///
+    /// # Errors
+    /// - [`PropertyError::NotNumber`] for properties that do not take numbers.
+    /// - [`PropertyError::ValueNotSupported`] if `v` is not finite.
+    /// - [`PropertyError::ValueOutOfRange`] or [`PropertyError::ValueNotSupported`] if the
+    ///   value is not valid for the property, see [`Property::validate`].
+    pub fn make_value_from_f64(&self, v: f64) -> PropertyResult<PropertyValue> {
+        use PropertyLims::*;
+        match &self.prop {
+            Bool { .. } | Duration { .. } | PixelFmt { .. } | EnumStr { .. } => {
+                Err(PropertyError::NotNumber)
+            }
+            _ if !v.is_finite() => Err(PropertyError::ValueNotSupported),
+            Float { .. } => {
+                let value = PropertyValue::Float(v);
+                self.validate(&value)?;
+                Ok(value)
+            }
+            // `as` saturates, so the range of the integer type is checked first
+            Int { .. } | EnumInt { .. }
+                if (i64::MIN as f64..i64::MAX as f64).contains(&v.round()) =>
+            {
+                self.make_value_from_i64(v.round() as i64)
+            }
+            Unsigned { .. } | EnumUnsigned { .. }
+                if (0.0..u64::MAX as f64).contains(&v.round()) =>
+            {
+                let value = PropertyValue::Unsigned(v.round() as u64);
+                self.validate(&value)?;
+                Ok(value)
+            }
+            EnumInt { .. } | EnumUnsigned { .. } => Err(PropertyError::ValueNotSupported),
+            Int { .. } | Unsigned { .. } => Err(PropertyError::ValueOutOfRange {
+                min: self.get_min()?,
+                max: self.get_max()?,
+                value: PropertyValue::Float(v),
+            }),
+        }
+    }
+
     /// Get the minimum value of the property
     pub fn get_min(&self) -> PropertyResult<PropertyValue> {
         use PropertyLims::*;
@@ -260,6 +437,139 @@ impl Property {
             EnumUnsigned { variants, .. } => Ok(variants.iter().map(|x| (*x).into()).collect()),
         }
     }
+
+    /// Summarize the property in a single serializable [`PropertyDescription`].
+    pub fn describe(&self) -> PropertyDescription {
+        let is_enum = self.is_enum();
+        let numeric = |res: PropertyResult<PropertyValue>| if is_enum { None } else { res.ok() };
+        PropertyDescription {
+            kind: self.get_type(),
+            is_enum,
+            min: numeric(self.get_min()),
+            max: numeric(self.get_max()),
+            step: numeric(self.get_step()),
+            default: self.get_default().ok(),
+            variants: self.get_variants().ok(),
+            auto_supported: self.auto,
+            read_only: self.rdonly,
+            doc: self.doc.clone(),
+        }
+    }
+
+    /// Clamp a value to the limits of the property.
+    ///
+    /// - Numeric and duration values are clamped to `[min, max]` and snapped to the
+    ///   nearest multiple of `step` counted from `min`, never exceeding `max`.
+    /// - Enum and pixel format values are returned unchanged if they are a valid variant,
+    ///   otherwise the default value is returned.
+    /// - Boolean values are returned unchanged.
+    ///
+    /// Returns [`PropertyError::InvalidControlType`] if the value does not match the type
+    /// of the property.
+    pub fn clamp(&self, value: &PropertyValue) -> PropertyResult<PropertyValue> {
+        use PropertyLims::*;
+        match (&self.prop, value) {
+            (Bool { .. }, PropertyValue::Bool(_)) => Ok(value.clone()),
+            (Int { min, max, step, .. }, PropertyValue::Int(val)) => {
+                Ok((snap_to_step(*val as _, *min as _, *max as _, *step as _) as i64).into())
+            }
+            (Unsigned { min, max, step, .. }, PropertyValue::Unsigned(val)) => {
+                Ok((snap_to_step(*val as _, *min as _, *max as _, *step as _) as u64).into())
+            }
+            (Duration { min, max, step, .. }, PropertyValue::Duration(val)) => {
+                let nanos = snap_to_step(
+                    val.as_nanos() as _,
+                    min.as_nanos() as _,
+                    max.as_nanos() as _,
+                    step.as_nanos() as _,
+                );
+                Ok(std::time::Duration::new(
+                    (nanos / 1_000_000_000) as u64,
+                    (nanos % 1_000_000_000) as u32,
+                )
+                .into())
+            }
+            (Float { min, max, step, .. }, PropertyValue::Float(val)) => {
+                let val = val.max(*min).min(*max);
+                if !(*step > 0.0 && step.is_finite()) {
+                    return Ok(val.into());
+                }
+                // tolerate rounding errors when `max - min` is a multiple of `step`
+                let nmax = ((max - min) / step + 1e-9).floor();
+                let n = ((val - min) / step).round().min(nmax);
+                Ok((min + n * step).min(*max).into())
+            }
+            (PixelFmt { variants, default }, PropertyValue::PixelFmt(val)) => Ok(variants
+                .iter()
+                .find(|&v| v == val)
+                .unwrap_or(default)
+                .to_owned()
+                .into()),
+            (EnumStr { variants, default }, PropertyValue::EnumStr(val)) => Ok(variants
+                .iter()
+                .find(|&v| v == val)
+                .unwrap_or(default)
+                .to_owned()
+                .into()),
+            (EnumInt { variants, default }, PropertyValue::Int(val)) => Ok(variants
+                .iter()
+                .find(|&v| v == val)
+                .unwrap_or(default)
+                .to_owned()
+                .into()),
+            (EnumUnsigned { variants, default }, PropertyValue::Unsigned(val)) => Ok(variants
+                .iter()
+                .find(|&v| v == val)
+                .unwrap_or(default)
+                .to_owned()
+                .into()),
+            _ => Err(PropertyError::InvalidControlType {
+                expected: self.get_type(),
+                received: value.get_type(),
+            }),
+        }
+    }
+}
+
+/// Clamp `val` to `[min, max]` and round it to the nearest `min + n * step` that does not exceed `max`.
+fn snap_to_step(val: i128, min: i128, max: i128, step: i128) -> i128 {
+    let val = val.max(min).min(max);
+    if step <= 0 {
+        return val;
+    }
+    let (n, rem) = ((val - min) / step, (val - min) % step);
+    let n = if 2 * rem >= step { n + 1 } else { n };
+    let n = n.min((max - min) / step);
+    min + n * step
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// A summary of a [`Property`], returned by [`Property::describe`].
+///
+/// The optional fields are only populated where they apply to the property:
+/// `min`, `max` and `step` for numeric and duration properties, and `variants`
+/// for enum-like properties.
+pub struct PropertyDescription {
+    /// The type of the property
+    pub kind: PropertyType,
+    /// Whether the property takes one of a list of variants
+    pub is_enum: bool,
+    /// The minimum value
+    pub min: Option<PropertyValue>,
+    /// The maximum value
+    pub max: Option<PropertyValue>,
+    /// The step size
+    pub step: Option<PropertyValue>,
+    /// The default value
+    pub default: Option<PropertyValue>,
+    /// The allowed values of an enum-like property
+    pub variants: Option<Vec<PropertyValue>>,
+    /// Whether the property supports auto mode
+    pub auto_supported: bool,
+    /// Whether the property is read-only
+    pub read_only: bool,
+    /// The documentation string
+    pub doc: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -345,9 +655,14 @@ pub enum PropertyLims {
     },
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, PartialOrd)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[non_exhaustive]
 /// A property value
+///
+/// Values are equal only if they are of the same variant, e.g.
+/// `PropertyValue::Int(5) != PropertyValue::Unsigned(5)`. Numeric values are ordered by
+/// magnitude, even across variants, see [`PropertyValue::numeric_cmp`]; values of
+/// different variants with the same magnitude are unordered, consistent with equality.
 pub enum PropertyValue {
     /// A command
     Command,
@@ -372,6 +687,145 @@ impl PropertyValue {
     pub fn get_type(&self) -> PropertyType {
         self.into()
     }
+
+    /// Compare two values by magnitude, as used by [`Property::validate`].
+    ///
+    /// Numeric values ([`PropertyValue::Int`], [`PropertyValue::Unsigned`] and
+    /// [`PropertyValue::Float`]) are compared even across variants, so that e.g.
+    /// `Int(5)` is greater than `Unsigned(3)` and equal to `Unsigned(5)`. Integers are
+    /// compared exactly. Other values only compare with values of the same variant;
+    /// comparisons across kinds return `None`.
+    pub fn numeric_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        use PropertyValue::*;
+        match (self, other) {
+            (Int(_) | Unsigned(_), Int(_) | Unsigned(_)) => {
+                self.as_i128()?.partial_cmp(&other.as_i128()?)
+            }
+            (Int(_) | Unsigned(_) | Float(_), Int(_) | Unsigned(_) | Float(_)) => {
+                self.as_f64()?.partial_cmp(&other.as_f64()?)
+            }
+            (Duration(a), Duration(b)) => a.partial_cmp(b),
+            (Command, Command) => Some(std::cmp::Ordering::Equal),
+            (Bool(a), Bool(b)) => a.partial_cmp(b),
+            (PixelFmt(a), PixelFmt(b)) => a.partial_cmp(b),
+            (EnumStr(a), EnumStr(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+impl PartialOrd for PropertyValue {
+    /// Compare by magnitude with [`PropertyValue::numeric_cmp`], except that values of
+    /// different variants with the same magnitude are unordered, since they are not equal.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match self.numeric_cmp(other)? {
+            std::cmp::Ordering::Equal
+                if std::mem::discriminant(self) != std::mem::discriminant(other) =>
+            {
+                None
+            }
+            ord => Some(ord),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A [`PropertyValue`] that implements [`Eq`] and [`Hash`], for use as a map or set key.
+///
+/// Like [`PropertyValue`], values of different variants are always distinct keys,
+/// e.g. `PropertyValue::Int(5)` and `PropertyValue::Unsigned(5)`. Unlike it, floating
+/// point values are compared by their bits, except that all NaNs are the same key, and
+/// `0.0` and `-0.0` are the same key.
+pub struct HashablePropertyValue(pub PropertyValue);
+
+impl HashablePropertyValue {
+    /// Get the wrapped value.
+    pub fn into_inner(self) -> PropertyValue {
+        self.0
+    }
+
+    /// The bits identifying a floating point key.
+    fn float_bits(value: f64) -> u64 {
+        if value.is_nan() {
+            f64::NAN.to_bits()
+        } else if value == 0.0 {
+            0
+        } else {
+            value.to_bits()
+        }
+    }
+}
+
+impl From<PropertyValue> for HashablePropertyValue {
+    fn from(value: PropertyValue) -> Self {
+        Self(value)
+    }
+}
+
+impl PartialEq for HashablePropertyValue {
+    fn eq(&self, other: &Self) -> bool {
+        use PropertyValue::*;
+        match (&self.0, &other.0) {
+            (Float(a), Float(b)) => Self::float_bits(*a) == Self::float_bits(*b),
+            (Command, Command) => true,
+            (Bool(a), Bool(b)) => a == b,
+            (Int(a), Int(b)) => a == b,
+            (Unsigned(a), Unsigned(b)) => a == b,
+            (PixelFmt(a), PixelFmt(b)) => a == b,
+            (Duration(a), Duration(b)) => a == b,
+            (EnumStr(a), EnumStr(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for HashablePropertyValue {}
+
+impl std::hash::Hash for HashablePropertyValue {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        use PropertyValue::*;
+        std::mem::discriminant(&self.0).hash(state);
+        match &self.0 {
+            Command => {}
+            Bool(v) => v.hash(state),
+            Int(v) => v.hash(state),
+            Float(v) => Self::float_bits(*v).hash(state),
+            Unsigned(v) => v.hash(state),
+            PixelFmt(v) => v.hash(state),
+            Duration(v) => v.hash(state),
+            EnumStr(v) => v.hash(state),
+        }
+    }
+}
+
+impl std::fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyValue::Command => f.write_str("Command"),
+            PropertyValue::Bool(v) => write!(f, "{v}"),
+            PropertyValue::Int(v) => write!(f, "{v}"),
+            PropertyValue::Float(v) => write!(f, "{v}"),
+            PropertyValue::Unsigned(v) => write!(f, "{v}"),
+            PropertyValue::PixelFmt(v) => write!(f, "{v:?}"),
+            PropertyValue::Duration(v) => fmt_duration(v, f),
+            PropertyValue::EnumStr(v) => f.write_str(v),
+        }
+    }
+}
+
+/// Format a duration in the largest unit that fits, e.g. `1.5s` or `20ms`.
+fn fmt_duration(dur: &Duration, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let nanos = dur.as_nanos();
+    let (scale, unit) = if nanos >= 1_000_000_000 {
+        (1e9, "s")
+    } else if nanos >= 1_000_000 {
+        (1e6, "ms")
+    } else if nanos >= 1_000 {
+        (1e3, "us")
+    } else {
+        (1.0, "ns")
+    };
+    write!(f, "{}{unit}", nanos as f64 / scale)
 }
 
 impl From<()> for PropertyValue {
@@ -539,10 +993,29 @@ impl PropertyValue {
             _ => None,
         }
     }
-    /// Get the value as a floating point number
+    /// Get the value as a floating point number, widening numeric variants.
+    ///
+    /// - [`PropertyValue::Float`] is returned as-is.
+    /// - [`PropertyValue::Int`] and [`PropertyValue::Unsigned`] are converted with `as`,
+    ///   which may lose precision beyond 2^53.
+    /// - [`PropertyValue::Duration`] is returned as total seconds ([`Duration::as_secs_f64`]).
+    ///
+    /// Returns `None` for all other variants.
     pub fn as_f64(&self) -> Option<f64> {
         match self {
             PropertyValue::Float(val) => Some(*val),
+            PropertyValue::Int(val) => Some(*val as f64),
+            PropertyValue::Unsigned(val) => Some(*val as f64),
+            PropertyValue::Duration(val) => Some(val.as_secs_f64()),
+            _ => None,
+        }
+    }
+    /// Get an integer-like value ([`PropertyValue::Int`] or [`PropertyValue::Unsigned`])
+    /// as an [`i128`], so that the two can be compared without loss of precision.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            PropertyValue::Int(val) => Some(*val as i128),
+            PropertyValue::Unsigned(val) => Some(*val as i128),
             _ => None,
         }
     }
@@ -664,4 +1137,667 @@ pub enum PropertyError {
     #[error("Empty enum list")]
     /// Empty enum list.
     EmptyEnumList,
+    #[error("Name too long: {0} bytes")]
+    /// Name exceeds the maximum length of a [`CustomName`](crate::controls::CustomName).
+    NameTooLong(usize),
+}
+
+#[cfg(feature = "schema")]
+#[cfg_attr(docsrs, doc(cfg(feature = "schema")))]
+/// A JSON Schema (draft 2020-12) describing the serialized form of a [`Property`].
+///
+/// The schema follows the default `serde` representation used by this crate, and the
+/// definitions for [`PropertyValue`] and [`PropertyLims`] are available under `$defs`.
+/// This allows clients in other languages to validate remote configurations before
+/// sending them to a camera server.
+pub fn property_json_schema() -> serde_json::Value {
+    use serde_json::json;
+
+    fn tagged(variant: &str, schema: serde_json::Value) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": { variant: schema },
+            "required": [variant],
+            "additionalProperties": false,
+        })
+    }
+
+    fn lims(variant: &str, value: serde_json::Value) -> serde_json::Value {
+        tagged(
+            variant,
+            json!({
+                "type": "object",
+                "properties": {
+                    "min": value,
+                    "max": value,
+                    "step": value,
+                    "default": value,
+                },
+                "required": ["min", "max", "step", "default"],
+                "additionalProperties": false,
+            }),
+        )
+    }
+
+    fn enum_lims(variant: &str, value: serde_json::Value) -> serde_json::Value {
+        tagged(
+            variant,
+            json!({
+                "type": "object",
+                "properties": {
+                    "variants": { "type": "array", "items": value },
+                    "default": value,
+                },
+                "required": ["variants", "default"],
+                "additionalProperties": false,
+            }),
+        )
+    }
+
+    let int = json!({ "type": "integer" });
+    let unsigned = json!({ "type": "integer", "minimum": 0 });
+    let float = json!({ "type": "number" });
+    let duration = json!({ "$ref": "#/$defs/Duration" });
+    let bpp = json!({ "$ref": "#/$defs/GenCamPixelBpp" });
+    let string = json!({ "type": "string" });
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Property",
+        "type": "object",
+        "properties": {
+            "auto": { "type": "boolean" },
+            "rdonly": { "type": "boolean" },
+            "prop": { "$ref": "#/$defs/PropertyLims" },
+            "doc": { "type": ["string", "null"] },
+        },
+        "required": ["auto", "rdonly", "prop", "doc"],
+        "additionalProperties": false,
+        "$defs": {
+            "Duration": {
+                "type": "object",
+                "properties": {
+                    "secs": { "type": "integer", "minimum": 0 },
+                    "nanos": { "type": "integer", "minimum": 0, "maximum": 999_999_999 },
+                },
+                "required": ["secs", "nanos"],
+                "additionalProperties": false,
+            },
+            "GenCamPixelBpp": {
+                "enum": ["Bpp8", "Bpp10", "Bpp12", "Bpp16", "Bpp24", "Bpp32"],
+            },
+            "PropertyValue": {
+                "oneOf": [
+                    { "const": "Command" },
+                    tagged("Bool", json!({ "type": "boolean" })),
+                    tagged("Int", int.clone()),
+                    tagged("Float", float.clone()),
+                    tagged("Unsigned", unsigned.clone()),
+                    tagged("PixelFmt", bpp.clone()),
+                    tagged("Duration", duration.clone()),
+                    tagged("EnumStr", string.clone()),
+                ],
+            },
+            "PropertyLims": {
+                "oneOf": [
+                    tagged(
+                        "Bool",
+                        json!({
+                            "type": "object",
+                            "properties": { "default": { "type": "boolean" } },
+                            "required": ["default"],
+                            "additionalProperties": false,
+                        }),
+                    ),
+                    lims("Int", int.clone()),
+                    lims("Float", float),
+                    lims("Unsigned", unsigned.clone()),
+                    lims("Duration", duration),
+                    enum_lims("PixelFmt", bpp),
+                    enum_lims("EnumStr", string),
+                    enum_lims("EnumInt", int),
+                    enum_lims("EnumUnsigned", unsigned),
+                ],
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn roundtrip<T>(val: T)
+    where
+        T: Clone + PartialEq + std::fmt::Debug + Into<PropertyValue>,
+        T: TryFrom<PropertyValue, Error = PropertyError>,
+        T: for<'a> TryFrom<&'a PropertyValue, Error = PropertyError>,
+    {
+        let prop: PropertyValue = val.clone().into();
+        assert_eq!(T::try_from(&prop), Ok(val.clone()));
+        assert_eq!(T::try_from(prop), Ok(val));
+    }
+
+    #[test]
+    fn hashable_value() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        assert!(set.insert(HashablePropertyValue(PropertyValue::Int(5))));
+        assert!(!set.insert(PropertyValue::Int(5).into()));
+        // equal as property values, but distinct keys
+        assert!(set.insert(PropertyValue::Unsigned(5).into()));
+        assert!(set.insert(PropertyValue::Float(5.0).into()));
+        assert!(set.insert(PropertyValue::Float(1.5).into()));
+        assert!(!set.insert(PropertyValue::Float(1.5).into()));
+        assert!(set.insert(PropertyValue::Float(f64::NAN).into()));
+        assert!(!set.insert(PropertyValue::Float(-f64::NAN).into()));
+        assert!(set.insert(PropertyValue::Float(0.0).into()));
+        assert!(!set.insert(PropertyValue::Float(-0.0).into()));
+        assert!(set.insert(PropertyValue::EnumStr("Off".into()).into()));
+        assert_eq!(set.len(), 7);
+        assert!(set.contains(&HashablePropertyValue(PropertyValue::Float(1.5))));
+    }
+
+    #[test]
+    fn constructors() {
+        let prop = Property::percent_float();
+        assert_eq!(prop.get_min(), Ok(PropertyValue::Float(0.0)));
+        assert_eq!(prop.get_max(), Ok(PropertyValue::Float(100.0)));
+        assert_eq!(prop.get_step(), Ok(PropertyValue::Float(1.0)));
+        assert!(!prop.is_readonly() && !prop.supports_auto());
+
+        let prop = Property::exposure_duration(Duration::from_millis(1), Duration::from_secs(60));
+        assert_eq!(prop.get_type(), PropertyType::Duration);
+        assert_eq!(prop.get_default(), Ok(Duration::from_secs(1).into()));
+        let prop = Property::exposure_duration(Duration::from_millis(1), Duration::from_millis(10));
+        assert_eq!(prop.get_default(), Ok(Duration::from_millis(10).into()));
+
+        let prop = Property::boolean(true);
+        assert_eq!(prop.get_default(), Ok(PropertyValue::Bool(true)));
+    }
+
+    #[test]
+    fn make_value() {
+        let int = Property::new(
+            PropertyLims::Int {
+                min: -10,
+                max: 10,
+                step: 1,
+                default: 0,
+            },
+            false,
+            false,
+        );
+        assert!(matches!(
+            int.make_value_from_i64(-5),
+            Ok(PropertyValue::Int(-5))
+        ));
+        assert!(matches!(
+            int.make_value_from_f64(-2.6),
+            Ok(PropertyValue::Int(-3))
+        ));
+        assert!(matches!(
+            int.make_value_from_i64(11),
+            Err(PropertyError::ValueOutOfRange { .. })
+        ));
+
+        let unsigned = Property::new(
+            PropertyLims::Unsigned {
+                min: 0,
+                max: 100,
+                step: 1,
+                default: 0,
+            },
+            false,
+            false,
+        );
+        assert!(matches!(
+            unsigned.make_value_from_i64(42),
+            Ok(PropertyValue::Unsigned(42))
+        ));
+        assert_eq!(
+            unsigned.make_value_from_i64(-1),
+            Err(PropertyError::ValueOutOfRange {
+                min: PropertyValue::Unsigned(0),
+                max: PropertyValue::Unsigned(100),
+                value: PropertyValue::Int(-1),
+            })
+        );
+        assert!(matches!(
+            unsigned.make_value_from_f64(-3.0),
+            Err(PropertyError::ValueOutOfRange { .. })
+        ));
+        assert_eq!(
+            unsigned.make_value_from_f64(f64::NAN),
+            Err(PropertyError::ValueNotSupported)
+        );
+
+        let wide = Property::new(
+            PropertyLims::Unsigned {
+                min: 0,
+                max: u64::MAX,
+                step: 1,
+                default: 0,
+            },
+            false,
+            false,
+        );
+        // past the range of i64, but not of u64
+        assert_eq!(
+            wide.make_value_from_f64(1e19),
+            Ok(PropertyValue::Unsigned(10_000_000_000_000_000_000))
+        );
+        assert_eq!(
+            wide.make_value_from_f64(1e20),
+            Err(PropertyError::ValueOutOfRange {
+                min: PropertyValue::Unsigned(0),
+                max: PropertyValue::Unsigned(u64::MAX),
+                value: PropertyValue::Float(1e20),
+            })
+        );
+        assert!(matches!(
+            int.make_value_from_f64(-1e19),
+            Err(PropertyError::ValueOutOfRange { .. })
+        ));
+
+        let enum_unsigned = Property::new(
+            PropertyLims::EnumUnsigned {
+                variants: vec![1, 2, 4],
+                default: 1,
+            },
+            false,
+            false,
+        );
+        assert!(matches!(
+            enum_unsigned.make_value_from_i64(4),
+            Ok(PropertyValue::Unsigned(4))
+        ));
+        assert_eq!(
+            enum_unsigned.make_value_from_i64(-1),
+            Err(PropertyError::ValueNotSupported)
+        );
+        assert_eq!(
+            enum_unsigned.make_value_from_f64(1e20),
+            Err(PropertyError::ValueNotSupported)
+        );
+
+        let float = Property::new(
+            PropertyLims::Float {
+                min: 0.0,
+                max: 1.0,
+                step: 0.1,
+                default: 0.0,
+            },
+            false,
+            false,
+        );
+        assert!(matches!(
+            float.make_value_from_i64(1),
+            Ok(PropertyValue::Float(1.0))
+        ));
+        let boolean = Property::new(PropertyLims::Bool { default: false }, false, false);
+        assert_eq!(
+            boolean.make_value_from_i64(1),
+            Err(PropertyError::NotNumber)
+        );
+    }
+
+    #[test]
+    fn tryfrom_roundtrip() {
+        roundtrip(true);
+        roundtrip(-42i64);
+        roundtrip(42u64);
+        roundtrip(1.5f64);
+        roundtrip(Duration::from_millis(1500));
+        roundtrip("Mono8".to_string());
+        roundtrip(GenCamPixelBpp::Bpp12);
+        assert_eq!(<()>::try_from(PropertyValue::Command), Ok(()));
+    }
+
+    #[test]
+    fn tryfrom_no_coercion() {
+        assert_eq!(
+            u64::try_from(PropertyValue::Int(-1)),
+            Err(PropertyError::InvalidControlType {
+                expected: PropertyType::Unsigned,
+                received: PropertyType::Int,
+            })
+        );
+        assert_eq!(
+            i64::try_from(&PropertyValue::Unsigned(1)),
+            Err(PropertyError::InvalidControlType {
+                expected: PropertyType::Int,
+                received: PropertyType::Unsigned,
+            })
+        );
+        assert!(f64::try_from(PropertyValue::Int(1)).is_err());
+        assert!(bool::try_from(PropertyValue::Command).is_err());
+    }
+
+    #[test]
+    fn widening() {
+        assert_eq!(PropertyValue::Int(-3).as_f64(), Some(-3.0));
+        assert_eq!(PropertyValue::Unsigned(3).as_f64(), Some(3.0));
+        assert_eq!(
+            PropertyValue::Duration(Duration::from_millis(1500)).as_f64(),
+            Some(1.5)
+        );
+        assert_eq!(PropertyValue::Bool(true).as_f64(), None);
+
+        let big = i64::MAX as u64 + 1;
+        assert_eq!(
+            PropertyValue::Unsigned(big).as_i128(),
+            Some(i64::MAX as i128 + 1)
+        );
+        assert_eq!(
+            PropertyValue::Unsigned(u64::MAX).as_i128(),
+            Some(u64::MAX as i128)
+        );
+        assert_eq!(
+            PropertyValue::Int(i64::MIN).as_i128(),
+            Some(i64::MIN as i128)
+        );
+        assert_eq!(PropertyValue::Float(1.0).as_i128(), None);
+    }
+
+    #[test]
+    fn readonly() {
+        let lims = PropertyLims::Bool { default: false };
+        let prop = Property::new(lims.clone(), false, true);
+        assert!(prop.is_readonly());
+        assert_eq!(prop.check_writable(), Err(PropertyError::ReadOnly));
+        let prop = Property::new(lims, false, false);
+        assert!(!prop.is_readonly());
+        assert_eq!(prop.check_writable(), Ok(()));
+    }
+
+    #[test]
+    fn validate_write() {
+        let lims = PropertyLims::Int {
+            min: 0,
+            max: 10,
+            step: 1,
+            default: 0,
+        };
+        let prop = Property::new(lims.clone(), true, false);
+        assert_eq!(prop.validate_write(&PropertyValue::Int(5), false), Ok(()));
+        assert_eq!(prop.validate_write(&PropertyValue::Int(5), true), Ok(()));
+        assert!(matches!(
+            prop.validate_write(&PropertyValue::Int(11), false),
+            Err(PropertyError::ValueOutOfRange { .. })
+        ));
+        assert!(matches!(
+            prop.validate_write(&PropertyValue::Float(5.0), false),
+            Err(PropertyError::InvalidControlType { .. })
+        ));
+        let prop = Property::new(lims.clone(), false, false);
+        assert_eq!(
+            prop.validate_write(&PropertyValue::Int(5), true),
+            Err(PropertyError::AutoNotSupported)
+        );
+        let prop = Property::new(lims, true, true);
+        assert_eq!(
+            prop.validate_write(&PropertyValue::Int(5), false),
+            Err(PropertyError::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn variant_kind() {
+        let enums = [
+            (
+                PropertyLims::EnumStr {
+                    variants: vec!["Mono8".into()],
+                    default: "Mono8".into(),
+                },
+                PropertyType::EnumStr,
+            ),
+            (
+                PropertyLims::EnumInt {
+                    variants: vec![-1, 1],
+                    default: 1,
+                },
+                PropertyType::EnumInt,
+            ),
+            (
+                PropertyLims::EnumUnsigned {
+                    variants: vec![1, 2],
+                    default: 1,
+                },
+                PropertyType::EnumUnsigned,
+            ),
+            (
+                PropertyLims::PixelFmt {
+                    variants: vec![GenCamPixelBpp::Bpp8],
+                    default: GenCamPixelBpp::Bpp8,
+                },
+                PropertyType::PixelFmt,
+            ),
+        ];
+        for (lims, kind) in enums {
+            let prop = Property::new(lims, false, false);
+            assert!(prop.is_enum());
+            assert_eq!(prop.variant_kind(), Some(kind));
+        }
+        let prop = Property::new(
+            PropertyLims::Unsigned {
+                min: 1,
+                max: 2,
+                step: 1,
+                default: 1,
+            },
+            false,
+            false,
+        );
+        assert!(!prop.is_enum());
+        assert_eq!(prop.variant_kind(), None);
+    }
+
+    #[test]
+    fn clamp_float() {
+        let prop = Property::new(
+            PropertyLims::Float {
+                min: 0.1,
+                max: 1.0,
+                step: 0.3,
+                default: 0.1,
+            },
+            false,
+            false,
+        );
+        let clamp = |v: f64| prop.clamp(&v.into()).unwrap().as_f64().unwrap();
+        assert!((clamp(-5.0) - 0.1).abs() < 1e-12);
+        assert!((clamp(0.5) - 0.4).abs() < 1e-12);
+        assert!((clamp(0.6) - 0.7).abs() < 1e-12);
+        assert!((clamp(0.9) - 1.0).abs() < 1e-12);
+        assert!(clamp(0.9) <= 1.0);
+        assert!(clamp(10.0) <= 1.0);
+        assert!(prop.clamp(&PropertyValue::Int(1)).is_err());
+    }
+
+    #[test]
+    fn clamp_duration() {
+        let prop = Property::new(
+            PropertyLims::Duration {
+                min: Duration::from_millis(1),
+                max: Duration::from_millis(100),
+                step: Duration::from_millis(2),
+                default: Duration::from_millis(1),
+            },
+            false,
+            false,
+        );
+        let clamp = |v: Duration| prop.clamp(&v.into()).unwrap().as_duration().unwrap();
+        assert_eq!(clamp(Duration::ZERO), Duration::from_millis(1));
+        assert_eq!(clamp(Duration::from_micros(3900)), Duration::from_millis(3));
+        assert_eq!(clamp(Duration::from_micros(4100)), Duration::from_millis(5));
+        // 101ms would be the nearest step, but exceeds the maximum
+        assert_eq!(clamp(Duration::from_secs(1)), Duration::from_millis(99));
+    }
+
+    #[test]
+    fn clamp_enum() {
+        let prop = Property::new(
+            PropertyLims::EnumUnsigned {
+                variants: vec![1, 2, 4],
+                default: 1,
+            },
+            false,
+            false,
+        );
+        assert_eq!(prop.clamp(&4u64.into()), Ok(4u64.into()));
+        assert_eq!(prop.clamp(&3u64.into()), Ok(1u64.into()));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(PropertyValue::Command.to_string(), "Command");
+        assert_eq!(PropertyValue::Bool(true).to_string(), "true");
+        assert_eq!(PropertyValue::Int(-3).to_string(), "-3");
+        assert_eq!(PropertyValue::Float(1.5).to_string(), "1.5");
+        assert_eq!(PropertyValue::Unsigned(42).to_string(), "42");
+        assert_eq!(
+            PropertyValue::PixelFmt(GenCamPixelBpp::Bpp8).to_string(),
+            "Bpp8"
+        );
+        assert_eq!(PropertyValue::EnumStr("Auto".into()).to_string(), "Auto");
+        let dur = |d| PropertyValue::Duration(d).to_string();
+        assert_eq!(dur(Duration::from_secs(1)), "1s");
+        assert_eq!(dur(Duration::from_millis(1500)), "1.5s");
+        assert_eq!(dur(Duration::from_millis(20)), "20ms");
+        assert_eq!(dur(Duration::from_micros(250)), "250us");
+        assert_eq!(dur(Duration::from_nanos(7)), "7ns");
+        assert_eq!(dur(Duration::ZERO), "0ns");
+    }
+
+    #[test]
+    fn ordering() {
+        use PropertyValue::*;
+        assert!(Int(5) > Unsigned(3));
+        assert!(Unsigned(3) < Int(5));
+        assert!(Int(-1) < Unsigned(0));
+        assert!(Unsigned(u64::MAX) > Int(i64::MAX));
+        // equal magnitudes of different variants are not equal values
+        assert_ne!(Int(5), Unsigned(5));
+        assert_eq!(Int(5).partial_cmp(&Unsigned(5)), None);
+        assert_eq!(
+            Int(5).numeric_cmp(&Unsigned(5)),
+            Some(std::cmp::Ordering::Equal)
+        );
+        assert_ne!(Int(i64::MAX), Float(i64::MAX as f64));
+        assert!(Float(2.5) > Int(2));
+        assert!(Unsigned(3) < Float(3.5));
+        assert_eq!(
+            Duration(std::time::Duration::from_secs(1))
+                .partial_cmp(&Duration(std::time::Duration::from_millis(10))),
+            Some(std::cmp::Ordering::Greater)
+        );
+        assert_eq!(Int(1).partial_cmp(&Bool(true)), None);
+        assert_eq!(
+            Int(1).partial_cmp(&Duration(std::time::Duration::from_secs(1))),
+            None
+        );
+        assert_eq!(Float(f64::NAN).partial_cmp(&Float(f64::NAN)), None);
+        assert_ne!(Int(1), EnumStr("1".into()));
+
+        // `Property::validate` with values of a variant other than the limits
+        let prop = Property::new(
+            PropertyLims::Unsigned {
+                min: 0,
+                max: 10,
+                step: 1,
+                default: 0,
+            },
+            false,
+            false,
+        );
+        assert_eq!(prop.validate(&Unsigned(10)), Ok(()));
+        assert!(matches!(
+            prop.validate(&Unsigned(11)),
+            Err(PropertyError::ValueOutOfRange { .. })
+        ));
+        for value in [Int(5), Int(-1), Int(11), Float(5.0)] {
+            assert!(matches!(
+                prop.validate(&value),
+                Err(PropertyError::InvalidControlType {
+                    expected: PropertyType::Unsigned,
+                    ..
+                })
+            ));
+        }
+        let prop = Property::new(
+            PropertyLims::Int {
+                min: -5,
+                max: 5,
+                step: 1,
+                default: 0,
+            },
+            false,
+            false,
+        );
+        assert_eq!(prop.validate(&Int(-5)), Ok(()));
+        assert!(matches!(
+            prop.validate(&Int(6)),
+            Err(PropertyError::ValueOutOfRange { .. })
+        ));
+        assert!(matches!(
+            prop.validate(&Unsigned(3)),
+            Err(PropertyError::InvalidControlType { .. })
+        ));
+    }
+
+    #[test]
+    fn describe() {
+        let prop = Property::new(
+            PropertyLims::Float {
+                min: -1.0,
+                max: 1.0,
+                step: 0.5,
+                default: 0.0,
+            },
+            true,
+            false,
+        );
+        assert_eq!(
+            prop.describe(),
+            PropertyDescription {
+                kind: PropertyType::Float,
+                is_enum: false,
+                min: Some(PropertyValue::Float(-1.0)),
+                max: Some(PropertyValue::Float(1.0)),
+                step: Some(PropertyValue::Float(0.5)),
+                default: Some(PropertyValue::Float(0.0)),
+                variants: None,
+                auto_supported: true,
+                read_only: false,
+                doc: None,
+            }
+        );
+
+        let mut prop = Property::new(
+            PropertyLims::EnumStr {
+                variants: vec!["Off".into(), "On".into()],
+                default: "Off".into(),
+            },
+            false,
+            true,
+        );
+        prop.set_doc("Fan mode");
+        assert_eq!(
+            prop.describe(),
+            PropertyDescription {
+                kind: PropertyType::EnumStr,
+                is_enum: true,
+                min: None,
+                max: None,
+                step: None,
+                default: Some("Off".into()),
+                variants: Some(vec!["Off".into(), "On".into()]),
+                auto_supported: false,
+                read_only: true,
+                doc: Some("Fan mode".into()),
+            }
+        );
+    }
 }
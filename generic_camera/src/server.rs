@@ -1,13 +1,27 @@
 /*!
  * # Generic Camera Server
  * This module contains the implementation of a generic camera server that can manage multiple cameras.
+ *
+ * ## Wire format
+ * Cameras managed by a [`GenCamServer`] can be accessed over TCP using [`GenCamServer::serve_tcp`].
+ * All messages are sent as frames, consisting of:
+ * 1. The length of the payload in bytes, as a little-endian `u32`.
+ * 2. The payload, encoded using [`bincode`] 1.x with the default options (little-endian,
+ *    fixed-width integers, `u64` sequence lengths, `u32` enum discriminants).
+ *
+ * A client sends requests with a payload of `(u32, GenSrvCmd)`, i.e. the camera ID
+ * followed by the [`GenSrvCmd`] to execute. For every request, the server replies with
+ * a [`GenSrvOutput`] frame. Requests on a connection are processed in order.
  */
- // FIXME: port to new API
-use rand::{thread_rng, Rng};
 use refimage::GenericImageOwned;
 use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
 
 use crate::AnyGenCam;
+use crate::Capture;
 #[allow(unused_imports)]
 use crate::GenCam;
 use crate::GenCamCtrl;
@@ -45,6 +59,28 @@ pub enum GenSrvValue {
     State(GenCamState),
     /// A list of properties available on the camera.
     PropertyList(HashMap<GenCamCtrl, Property>),
+    /// The exposure is not ready yet.
+    ///
+    /// Contains the suggested time to wait before polling again, if available.
+    Wait(Option<Duration>),
+    /// The results of a [`GenSrvCmd::Batch`], in order.
+    Batch(Vec<GenSrvOutput>),
+    /// The IDs and descriptors of the cameras on the server, ordered by ID.
+    CameraList(Vec<(u32, GenCamDescriptor)>),
+}
+
+impl GenSrvValue {
+    /// Encode the value into a length-prefixed binary frame.
+    ///
+    /// See [`encode_frame`] for the framing format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_frame(self)
+    }
+
+    /// Decode a value from a length-prefixed binary frame created by [`GenSrvValue::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> GenCamResult<Self> {
+        decode_frame(bytes)
+    }
 }
 
 impl From<()> for GenSrvValue {
@@ -116,7 +152,7 @@ impl From<HashMap<GenCamCtrl, Property>> for GenSrvValue {
 }
 
 /// The possible calls that can be made to a generic camera server.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum GenSrvCmd {
     /// Get the vendor of the camera. Calls the [`GenCam::vendor`] method.
     Vendor,
@@ -126,36 +162,121 @@ pub enum GenSrvCmd {
     CameraName,
     /// Get the camera info. Calls the [`GenCam::info`] method.
     Info,
+    /// Get a single entry of [`GenCamDescriptor::info`] by its key, instead of the whole
+    /// descriptor. Returns a [`GenSrvValue::Property`], or
+    /// [`GenCamError::InfoFieldNotFound`] if there is no such entry.
+    GetInfoField(String),
     /// List all properties available on the camera. Calls the [`GenCam::list_properties`] method.
     ListProperties,
     /// Get a specific property from the camera. Calls the [`GenCam::get_property`] method.
     GetProperty(GenCamCtrl),
-    /// Set a specific property on the camera. Calls the [`GenCam::set_property`] method.
+    /// Set a specific property on the camera. Calls the [`GenCam::set_property`] method,
+    /// or the [`GenCam::set_property_auto`] method if the flag is set.
     SetProperty(GenCamCtrl, PropertyValue, bool),
     /// Cancel a capture in progress. Calls the [`GenCam::cancel_capture`] method.
     CancelCapture,
     /// Check if the camera is currently capturing. Calls the [`GenCam::is_capturing`] method.
     IsCapturing,
-    /// Capture an image from the camera. Calls the [`GenCam::capture`] method.
+    /// Capture an image from the camera. Calls the [`Capture::capture`] method.
     Capture,
     /// Start an exposure on the camera. Calls the [`GenCam::start_exposure`] method.
     StartExposure,
-    /// Download an image from the camera. Calls the [`GenCam::download_image`] method.
-    DownloadImage,
-    /// Check if an image is ready to be downloaded. Calls the [`GenCam::image_ready`] method.
-    ImageReady,
+    /// Poll an exposure started with [`GenSrvCmd::StartExposure`]. Calls the [`GenCam::poll_exposure`] method.
+    ///
+    /// Returns [`GenSrvValue::Image`] if the exposure is ready, or [`GenSrvValue::Wait`] otherwise.
+    PollExposure,
     /// Get the current state of the camera. Calls the [`GenCam::camera_state`] method.
     CameraState,
     /// Set the region of interest on the camera. Calls the [`GenCam::set_roi`] method.
     SetRoi(GenCamRoi),
     /// Get the current region of interest. Calls the [`GenCam::get_roi`] method.
     GetRoi,
+    /// Execute several commands in order on the same camera.
+    ///
+    /// Returns a [`GenSrvValue::Batch`] containing the result of each command; a failing
+    /// command does not stop the remaining ones. Batches can not be nested.
+    Batch(Vec<GenSrvCmd>),
+    /// List the cameras on the server. Calls the [`GenCamServer::descriptors`] method.
+    ///
+    /// Returns a [`GenSrvValue::CameraList`]. The camera ID of the request is ignored,
+    /// and this command can not be part of a [`GenSrvCmd::Batch`].
+    ListCameras,
+}
+
+impl GenSrvCmd {
+    /// Encode the command into a length-prefixed binary frame.
+    ///
+    /// See [`encode_frame`] for the framing format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_frame(self)
+    }
+
+    /// Decode a command from a length-prefixed binary frame created by [`GenSrvCmd::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> GenCamResult<Self> {
+        decode_frame(bytes)
+    }
+}
+
+/// Length of the frame header, in bytes.
+pub const FRAME_HEADER_LEN: usize = 4;
+
+/// Maximum payload length of a request frame accepted by the server, in bytes.
+pub const MAX_REQUEST_LEN: usize = 1 << 20;
+
+/// Maximum payload length of a reply frame, in bytes.
+///
+/// The server answers with [`GenCamError::InvalidSize`] instead of sending a larger
+/// reply, e.g. a very large uncompressed image, and the client refuses to allocate
+/// a larger frame.
+pub const MAX_REPLY_LEN: usize = 1 << 30;
+
+/// Encode a value into a length-prefixed binary frame.
+///
+/// A frame consists of a little-endian `u32` containing the length of the payload,
+/// followed by the payload encoded using the default [`bincode`] (1.x) options.
+///
+/// # Panics
+/// If the payload is longer than `u32::MAX` bytes, see [`try_encode_frame`].
+pub fn encode_frame<T: Serialize>(value: &T) -> Vec<u8> {
+    try_encode_frame(value).expect("Frame payload exceeds the u32 length prefix")
+}
+
+/// Encode a value into a length-prefixed binary frame, see [`encode_frame`].
+///
+/// # Errors
+/// - [`GenCamError::InvalidSize`] if the payload is longer than `u32::MAX` bytes, and
+///   its length can not be represented in the frame header.
+pub fn try_encode_frame<T: Serialize>(value: &T) -> GenCamResult<Vec<u8>> {
+    let payload = bincode::serialize(value).expect("Serialization to a Vec can not fail");
+    let len = u32::try_from(payload.len()).map_err(|_| GenCamError::InvalidSize(payload.len()))?;
+    let mut out = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+/// Decode a value from a length-prefixed binary frame created by [`encode_frame`].
+///
+/// # Errors
+/// - [`GenCamError::InvalidSize`] if the frame length does not match the header.
+/// - [`GenCamError::InvalidFormat`] if the payload could not be decoded.
+pub fn decode_frame<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> GenCamResult<T> {
+    let Some((header, payload)) = bytes.split_first_chunk::<FRAME_HEADER_LEN>() else {
+        return Err(GenCamError::InvalidSize(bytes.len()));
+    };
+    if u32::from_le_bytes(*header) as usize != payload.len() {
+        return Err(GenCamError::InvalidSize(bytes.len()));
+    }
+    bincode::deserialize(payload).map_err(|e| GenCamError::InvalidFormat(e.to_string()))
 }
 
 /// A generic camera server that can manage multiple cameras.
 ///
 /// Once a camera is added to the server, it can be accessed by its assigned ID.
 ///
+/// Each camera is behind its own lock, so calls on different cameras can run in
+/// parallel, while calls on the same camera are serialized.
+///
 /// # Examples
 /// ```rust,ignore
 /// use generic_camera::server::GenCamServer;
@@ -166,33 +287,64 @@ pub enum GenSrvCmd {
 /// ```
 #[derive(Debug, Default)]
 pub struct GenCamServer {
-    cameras: HashMap<u32, AnyGenCam>,
+    cameras: HashMap<u32, Mutex<AnyGenCam>>,
     infos: HashMap<u32, GenCamDescriptor>,
+    next_id: u32,
 }
 
 impl GenCamServer {
     /// Add a camera to the server and return the camera's assigned ID.
+    ///
+    /// IDs are assigned in increasing order starting from `0`, skipping IDs that are
+    /// in use, e.g. by cameras added with [`GenCamServer::add_camera_with_id`].
     pub fn add_camera(&mut self, camera: AnyGenCam) -> GenCamResult<u32> {
-        let id = thread_rng().gen();
+        let mut id = self.next_id;
+        while self.cameras.contains_key(&id) {
+            id = id.wrapping_add(1);
+            if id == self.next_id {
+                return Err(GenCamError::InvalidId(id as _));
+            }
+        }
+        self.add_camera_with_id(id, camera)?;
+        self.next_id = id.wrapping_add(1);
+        Ok(id)
+    }
+
+    /// Add a camera to the server with the given ID.
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidId`] if a camera with this ID is already on the server.
+    /// - Any error returned by [`GenCam::info`].
+    pub fn add_camera_with_id(&mut self, id: u32, camera: AnyGenCam) -> GenCamResult<()> {
+        if self.cameras.contains_key(&id) {
+            return Err(GenCamError::InvalidId(id as _));
+        }
         let info = camera.info()?.clone();
-        self.cameras.insert(id, camera);
+        self.cameras.insert(id, Mutex::new(camera));
         self.infos.insert(id, info);
-        Ok(id)
+        Ok(())
     }
 
-    /// Get a reference to a camera by its ID.
-    pub fn get_camera(&self, id: u32) -> Option<&AnyGenCam> {
-        self.cameras.get(&id)
+    /// Lock a camera by its ID, blocking until calls on it by other threads complete.
+    ///
+    /// Returns `None` if there is no such camera, or if a call on the camera panicked.
+    pub fn get_camera(&self, id: u32) -> Option<MutexGuard<'_, AnyGenCam>> {
+        self.cameras.get(&id)?.lock().ok()
     }
 
     /// Get a mutable reference to a camera by its ID.
     pub fn get_camera_mut(&mut self, id: u32) -> Option<&mut AnyGenCam> {
-        self.cameras.get_mut(&id)
+        self.cameras
+            .get_mut(&id)
+            .map(|camera| camera.get_mut().unwrap_or_else(PoisonError::into_inner))
     }
 
     /// Remove a camera from the server by its ID.
     pub fn remove_camera(&mut self, id: u32) -> Option<AnyGenCam> {
-        self.cameras.remove(&id)
+        self.infos.remove(&id);
+        self.cameras
+            .remove(&id)
+            .map(|camera| camera.into_inner().unwrap_or_else(PoisonError::into_inner))
     }
 
     /// Get the number of cameras currently connected to the server.
@@ -205,45 +357,365 @@ impl GenCamServer {
         &self.infos
     }
 
+    /// Get the IDs of the cameras on the server, in increasing order.
+    pub fn camera_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<_> = self.cameras.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Get the ID and descriptor of each camera on the server, ordered by ID.
+    ///
+    /// The descriptors are the ones returned by [`GenCam::info`] when the cameras
+    /// were added.
+    pub fn descriptors(&self) -> Vec<(u32, GenCamDescriptor)> {
+        let mut descs: Vec<_> = self
+            .infos
+            .iter()
+            .map(|(id, info)| (*id, info.clone()))
+            .collect();
+        descs.sort_unstable_by_key(|(id, _)| *id);
+        descs
+    }
+
+    /// Serve the cameras over TCP on the given address.
+    ///
+    /// See [`GenCamServer::serve`] for details.
+    pub fn serve_tcp(self, addr: impl ToSocketAddrs) -> GenCamResult<()> {
+        let listener =
+            TcpListener::bind(addr).map_err(|e| GenCamError::GeneralError(e.to_string()))?;
+        self.serve(listener)
+    }
+
+    /// Serve the cameras over TCP using an already bound listener.
+    ///
+    /// Each client connection is handled on its own thread. Requests are
+    /// executed using [`GenCamServer::execute_fn`]. Requests on different cameras
+    /// run in parallel, but requests on the same camera are executed one at a time
+    /// across all connections; a blocking call such as [`GenSrvCmd::Capture`] will
+    /// hold off other clients of that camera until it completes. See the
+    /// [module documentation](self) for the wire format.
+    ///
+    /// Malformed requests are answered with an error frame instead of closing the
+    /// connection. Requests larger than [`MAX_REQUEST_LEN`] are answered with
+    /// [`GenCamError::InvalidSize`], after which the connection is closed. Replies
+    /// larger than [`MAX_REPLY_LEN`] are replaced by [`GenCamError::InvalidSize`].
+    ///
+    /// This function does not return unless accepting connections fails.
+    pub fn serve(self, listener: TcpListener) -> GenCamResult<()> {
+        let server = Arc::new(self);
+        for stream in listener.incoming() {
+            let stream = stream.map_err(|e| GenCamError::GeneralError(e.to_string()))?;
+            let server = server.clone();
+            std::thread::spawn(move || handle_client(&server, stream));
+        }
+        Ok(())
+    }
+
     /// Execute a client call on a camera by its ID.
-    pub fn execute_fn(&mut self, id: u32, sig: GenSrvCmd) -> GenCamResult<GenSrvValue> {
-        let Some(camera) = self.get_camera_mut(id) else {
+    ///
+    /// Only the targeted camera is locked, so calls on other cameras can be executed
+    /// concurrently. Calls on the same camera block until the previous one completes.
+    ///
+    /// # Errors
+    /// - [`GenCamError::InvalidId`] if there is no camera with this ID.
+    /// - [`GenCamError::AccessViolation`] if a previous call on the camera panicked.
+    /// - Any error returned by the camera.
+    pub fn execute_fn(&self, id: u32, sig: GenSrvCmd) -> GenCamResult<GenSrvValue> {
+        if sig == GenSrvCmd::ListCameras {
+            return Ok(GenSrvValue::CameraList(self.descriptors()));
+        }
+        let Some(camera) = self.cameras.get(&id) else {
             return Err(GenCamError::InvalidId(id as _));
         };
-        use GenSrvCmd::*;
-        let res = match sig {
-            Vendor => {
-                let vendor = camera.vendor();
-                PropertyValue::EnumStr(vendor.to_string()).into()
-            }
-            CameraReady => {
-                let ready = camera.camera_ready();
-                PropertyValue::Bool(ready).into()
-            }
-            CameraName => {
-                let name = camera.camera_name();
-                PropertyValue::EnumStr(name.to_string()).into()
-            }
-            Info => {
-                let info = camera.info()?.clone();
-                info.into()
+        let mut camera = camera.lock().map_err(|_| GenCamError::AccessViolation)?;
+        execute_on(&mut camera, sig)
+    }
+}
+
+/// Execute a client call on a locked camera.
+fn execute_on(camera: &mut AnyGenCam, sig: GenSrvCmd) -> GenCamResult<GenSrvValue> {
+    use GenSrvCmd::*;
+    let res = match sig {
+        Vendor => {
+            let vendor = camera.vendor();
+            PropertyValue::EnumStr(vendor.to_string()).into()
+        }
+        CameraReady => {
+            let ready = camera.camera_ready();
+            PropertyValue::Bool(ready).into()
+        }
+        CameraName => {
+            let name = camera.camera_name();
+            PropertyValue::EnumStr(name.to_string()).into()
+        }
+        Info => {
+            let info = camera.info()?.clone();
+            info.into()
+        }
+        GetInfoField(key) => match camera.info()?.info.get(&key) {
+            Some(value) => GenSrvValue::Property {
+                value: value.clone(),
+                auto: None,
+            },
+            None => return Err(GenCamError::InfoFieldNotFound(key)),
+        },
+        ListProperties => {
+            let properties = camera.list_properties();
+            GenSrvValue::PropertyList(properties.clone())
+        }
+        GetProperty(ctrl) => camera.get_property(ctrl)?.into(),
+        SetProperty(ctrl, value, false) => camera.set_property(ctrl, &value)?.into(),
+        SetProperty(ctrl, value, true) => camera.set_property_auto(ctrl, &value)?.into(),
+        CancelCapture => camera.cancel_capture()?.into(),
+        IsCapturing => PropertyValue::Bool(camera.is_capturing()).into(),
+        Capture => GenSrvValue::Image(camera.capture()?.into()),
+        StartExposure => camera.start_exposure()?.into(),
+        PollExposure => match camera.poll_exposure() {
+            crate::PollExposure::Ready(img) => GenSrvValue::Image(img?.into()),
+            crate::PollExposure::Wait(dur) => GenSrvValue::Wait(Some(dur)),
+            crate::PollExposure::Soon => GenSrvValue::Wait(None),
+        },
+        CameraState => camera.camera_state()?.into(),
+        SetRoi(roi) => (*camera.set_roi(&roi)?).into(),
+        GetRoi => (*camera.get_roi()).into(),
+        // handled by the server
+        ListCameras => return Err(GenCamError::InvalidSequence),
+        Batch(cmds) => {
+            if cmds.iter().any(|cmd| matches!(cmd, Batch(_) | ListCameras)) {
+                return Err(GenCamError::InvalidSequence);
             }
-            ListProperties => {
-                let properties = camera.list_properties();
-                GenSrvValue::PropertyList(properties.clone())
+            GenSrvValue::Batch(
+                cmds.into_iter()
+                    .map(|cmd| execute_on(camera, cmd))
+                    .collect(),
+            )
+        }
+    };
+    Ok(res)
+}
+
+/// Handle requests from a single client until the connection is closed.
+fn handle_client(server: &GenCamServer, mut stream: TcpStream) -> std::io::Result<()> {
+    loop {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        match stream.read_exact(&mut header) {
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+            res => res?,
+        }
+        let len = u32::from_le_bytes(header) as usize;
+        if len > MAX_REQUEST_LEN {
+            // we can not skip the payload reliably, so drop the connection
+            let out: GenSrvOutput = Err(GenCamError::InvalidSize(len));
+            return stream.write_all(&encode_frame(&out));
+        }
+        let mut frame = vec![0u8; FRAME_HEADER_LEN + len];
+        frame[..FRAME_HEADER_LEN].copy_from_slice(&header);
+        stream.read_exact(&mut frame[FRAME_HEADER_LEN..])?;
+        let out: GenSrvOutput = decode_frame::<(u32, GenSrvCmd)>(&frame)
+            .and_then(|(id, cmd)| server.execute_fn(id, cmd));
+        stream.write_all(&encode_reply(&out))?;
+    }
+}
+
+/// Encode a reply frame, replacing replies longer than [`MAX_REPLY_LEN`] with an error.
+fn encode_reply(out: &GenSrvOutput) -> Vec<u8> {
+    let error = match try_encode_frame(out) {
+        Ok(frame) if frame.len() - FRAME_HEADER_LEN <= MAX_REPLY_LEN => return frame,
+        Ok(frame) => GenCamError::InvalidSize(frame.len() - FRAME_HEADER_LEN),
+        Err(e) => e,
+    };
+    encode_frame(&GenSrvOutput::Err(error))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GenCamDriver;
+    use crate::controls::ExposureCtrl;
+    use crate::dummy::GenCamDriverDummy;
+
+    #[test]
+    fn cmd_roundtrip() {
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let cmds = [
+            GenSrvCmd::Vendor,
+            GenSrvCmd::CameraReady,
+            GenSrvCmd::CameraName,
+            GenSrvCmd::Info,
+            GenSrvCmd::GetInfoField("Interface".into()),
+            GenSrvCmd::ListProperties,
+            GenSrvCmd::GetProperty(ctrl),
+            GenSrvCmd::SetProperty(ctrl, Duration::from_millis(10).into(), true),
+            GenSrvCmd::CancelCapture,
+            GenSrvCmd::IsCapturing,
+            GenSrvCmd::Capture,
+            GenSrvCmd::StartExposure,
+            GenSrvCmd::PollExposure,
+            GenSrvCmd::CameraState,
+            GenSrvCmd::SetRoi(GenCamRoi {
+                x_min: 1,
+                y_min: 2,
+                width: 3,
+                height: 4,
+            }),
+            GenSrvCmd::GetRoi,
+            GenSrvCmd::Batch(vec![GenSrvCmd::Vendor, GenSrvCmd::GetProperty(ctrl)]),
+            GenSrvCmd::ListCameras,
+        ];
+        for cmd in cmds {
+            let bytes = cmd.to_bytes();
+            assert_eq!(GenSrvCmd::from_bytes(&bytes), Ok(cmd));
+        }
+    }
+
+    #[test]
+    fn value_roundtrip() {
+        let mut server = GenCamServer::default();
+        let id = server
+            .add_camera(GenCamDriverDummy::default().connect_first_device().unwrap())
+            .unwrap();
+        for cmd in [
+            GenSrvCmd::Info,
+            GenSrvCmd::ListProperties,
+            GenSrvCmd::Capture,
+            GenSrvCmd::CameraState,
+            GenSrvCmd::GetRoi,
+        ] {
+            let value = server.execute_fn(id, cmd).unwrap();
+            let decoded = GenSrvValue::from_bytes(&value.to_bytes()).unwrap();
+            match (value, decoded) {
+                (GenSrvValue::Info(a), GenSrvValue::Info(b)) => assert_eq!(a, b),
+                (GenSrvValue::PropertyList(a), GenSrvValue::PropertyList(b)) => assert_eq!(a, b),
+                (GenSrvValue::Image(a), GenSrvValue::Image(b)) => {
+                    assert_eq!(a.get_image(), b.get_image())
+                }
+                (GenSrvValue::State(a), GenSrvValue::State(b)) => assert_eq!(a, b),
+                (GenSrvValue::Roi(a), GenSrvValue::Roi(b)) => assert_eq!(a, b),
+                (a, b) => panic!("Mismatched round trip: {a:?} != {b:?}"),
             }
-            GetProperty(ctrl) => camera.get_property(ctrl)?.into(),
-            SetProperty(ctrl, value, auto) => camera.set_property(ctrl, &value, auto)?.into(),
-            CancelCapture => camera.cancel_capture()?.into(),
-            IsCapturing => PropertyValue::Bool(camera.is_capturing()).into(),
-            Capture => GenSrvValue::Image(camera.capture()?.into()),
-            StartExposure => camera.start_exposure()?.into(),
-            DownloadImage => GenSrvValue::Image(camera.download_image()?.into()),
-            ImageReady => PropertyValue::Bool(camera.image_ready()?).into(),
-            CameraState => camera.camera_state()?.into(),
-            SetRoi(roi) => (*camera.set_roi(&roi)?).into(),
-            GetRoi => (*camera.get_roi()).into(),
+        }
+    }
+
+    #[test]
+    fn batch() {
+        let mut server = GenCamServer::default();
+        let id = server
+            .add_camera(GenCamDriverDummy::default().connect_first_device().unwrap())
+            .unwrap();
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        let res = server
+            .execute_fn(
+                id,
+                GenSrvCmd::Batch(vec![
+                    GenSrvCmd::SetProperty(ctrl, Duration::from_millis(5).into(), false),
+                    GenSrvCmd::Capture,
+                ]),
+            )
+            .unwrap();
+        let GenSrvValue::Batch(res) = res else {
+            panic!("Expected a batch");
         };
-        Ok(res)
+        assert_eq!(res.len(), 2);
+        assert!(matches!(res[0], Ok(GenSrvValue::Unit)));
+        assert!(matches!(res[1], Ok(GenSrvValue::Image(_))));
+
+        let nested = GenSrvCmd::Batch(vec![GenSrvCmd::Vendor, GenSrvCmd::Batch(vec![])]);
+        assert!(matches!(
+            server.execute_fn(id, nested),
+            Err(GenCamError::InvalidSequence)
+        ));
+    }
+
+    #[test]
+    fn cameras_run_in_parallel() {
+        let mut server = GenCamServer::default();
+        let mut slow = GenCamDriverDummy::default().connect_first_device().unwrap();
+        slow.set_exposure(Duration::from_secs(10)).unwrap();
+        let slow_info = slow.info_handle().unwrap();
+        let slow = server.add_camera(slow).unwrap();
+        let fast = server
+            .add_camera(GenCamDriverDummy::default().connect_first_device().unwrap())
+            .unwrap();
+        let ctrl = GenCamCtrl::Exposure(ExposureCtrl::ExposureTime);
+        server
+            .execute_fn(
+                fast,
+                GenSrvCmd::SetProperty(ctrl, Duration::from_millis(10).into(), false),
+            )
+            .unwrap();
+        let server = Arc::new(server);
+
+        let capture = {
+            let server = server.clone();
+            std::thread::spawn(move || server.execute_fn(slow, GenSrvCmd::Capture))
+        };
+        while !slow_info.is_capturing() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        // the other camera is not blocked by the capture in progress
+        let other = std::thread::spawn({
+            let server = server.clone();
+            move || server.execute_fn(fast, GenSrvCmd::Capture)
+        });
+        assert!(matches!(other.join().unwrap(), Ok(GenSrvValue::Image(_))));
+        assert!(slow_info.is_capturing());
+        slow_info.cancel_capture().unwrap();
+        assert_eq!(capture.join().unwrap().unwrap_err(), GenCamError::Cancelled);
+    }
+
+    #[test]
+    fn camera_ids() {
+        let dummy = || GenCamDriverDummy::default().connect_first_device().unwrap();
+        let mut server = GenCamServer::default();
+        assert_eq!(server.add_camera(dummy()), Ok(0));
+        assert_eq!(server.add_camera(dummy()), Ok(1));
+        assert_eq!(server.add_camera_with_id(3, dummy()), Ok(()));
+        assert_eq!(
+            server.add_camera_with_id(1, dummy()),
+            Err(GenCamError::InvalidId(1))
+        );
+        assert_eq!(server.add_camera(dummy()), Ok(2));
+        // 3 is taken
+        assert_eq!(server.add_camera(dummy()), Ok(4));
+        assert_eq!(server.num_cameras(), 5);
+    }
+
+    #[test]
+    fn list_cameras() {
+        let dummy = || GenCamDriverDummy::default().connect_first_device().unwrap();
+        let mut server = GenCamServer::default();
+        server.add_camera_with_id(7, dummy()).unwrap();
+        server.add_camera(dummy()).unwrap();
+        assert_eq!(server.camera_ids(), [0, 7]);
+        let Ok(GenSrvValue::CameraList(list)) = server.execute_fn(1234, GenSrvCmd::ListCameras)
+        else {
+            panic!("Expected a camera list");
+        };
+        assert_eq!(list, server.descriptors());
+        assert_eq!(list.len(), 2);
+        assert!(list.iter().all(|(_, desc)| desc.vendor == "Dummy"));
+        assert!(matches!(
+            server.execute_fn(0, GenSrvCmd::Batch(vec![GenSrvCmd::ListCameras])),
+            Err(GenCamError::InvalidSequence)
+        ));
+        server.remove_camera(7);
+        assert_eq!(server.camera_ids(), [0]);
+        assert_eq!(server.descriptors().len(), 1);
+    }
+
+    #[test]
+    fn malformed_frame() {
+        let mut bytes = GenSrvCmd::GetRoi.to_bytes();
+        bytes.push(0);
+        assert!(matches!(
+            GenSrvCmd::from_bytes(&bytes),
+            Err(GenCamError::InvalidSize(_))
+        ));
+        assert!(matches!(
+            GenSrvCmd::from_bytes(&[1, 0, 0, 0, 0xff]),
+            Err(GenCamError::InvalidFormat(_))
+        ));
+        assert!(GenSrvCmd::from_bytes(&[0, 0]).is_err());
     }
 }
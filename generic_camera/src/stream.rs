@@ -0,0 +1,222 @@
+/*!
+ * # Frame Streams
+ * This module provides the bounded channel used to deliver frames from a free-running
+ * camera, see [`GenCam::start_stream`](crate::GenCam::start_stream).
+ *
+ * Unlike [`std::sync::mpsc::sync_channel`], sending never blocks: when the channel is
+ * full, the oldest queued frame is dropped to make room for the new one. A slow
+ * consumer therefore always sees the most recent frames, and the camera is never
+ * stalled by the consumer.
+ */
+use std::collections::VecDeque;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// State shared by the two halves of a stream channel.
+#[derive(Debug)]
+struct Shared<T> {
+    queue: Mutex<Queue<T>>,
+    ready: Condvar,
+}
+
+#[derive(Debug)]
+struct Queue<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    dropped: u64,
+    sender_alive: bool,
+    receiver_alive: bool,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, Queue<T>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Create a stream channel holding at most `capacity` items (at least one).
+pub fn stream_channel<T>(capacity: usize) -> (StreamSender<T>, StreamReceiver<T>) {
+    let capacity = capacity.max(1);
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            dropped: 0,
+            sender_alive: true,
+            receiver_alive: true,
+        }),
+        ready: Condvar::new(),
+    });
+    (
+        StreamSender {
+            shared: shared.clone(),
+        },
+        StreamReceiver { shared },
+    )
+}
+
+/// The sending half of a stream channel, see [`stream_channel`].
+#[derive(Debug)]
+pub struct StreamSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> StreamSender<T> {
+    /// Queue `value`, dropping the oldest queued item if the channel is full.
+    ///
+    /// # Errors
+    /// Returns `value` if the receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut queue = self.shared.lock();
+        if !queue.receiver_alive {
+            return Err(SendError(value));
+        }
+        if queue.items.len() >= queue.capacity {
+            queue.items.pop_front();
+            queue.dropped += 1;
+        }
+        queue.items.push_back(value);
+        drop(queue);
+        self.shared.ready.notify_one();
+        Ok(())
+    }
+
+    /// Check if the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+}
+
+impl<T> Drop for StreamSender<T> {
+    fn drop(&mut self) {
+        self.shared.lock().sender_alive = false;
+        self.shared.ready.notify_all();
+    }
+}
+
+/// The receiving half of a stream channel, see [`stream_channel`].
+///
+/// Items queued before the sender is dropped can still be received; afterwards, the
+/// receive methods report a disconnection like [`std::sync::mpsc::Receiver`].
+#[derive(Debug)]
+pub struct StreamReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> StreamReceiver<T> {
+    /// Block until an item is available.
+    ///
+    /// # Errors
+    /// Returns [`RecvError`] if the queue is empty and the sender has been dropped.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let mut queue = self.shared.lock();
+        loop {
+            if let Some(item) = queue.items.pop_front() {
+                return Ok(item);
+            }
+            if !queue.sender_alive {
+                return Err(RecvError);
+            }
+            queue = self
+                .shared
+                .ready
+                .wait(queue)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Block for at most `timeout` until an item is available.
+    ///
+    /// # Errors
+    /// - [`RecvTimeoutError::Timeout`] if no item arrived in time.
+    /// - [`RecvTimeoutError::Disconnected`] if the queue is empty and the sender has
+    ///   been dropped.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.shared.lock();
+        loop {
+            if let Some(item) = queue.items.pop_front() {
+                return Ok(item);
+            }
+            if !queue.sender_alive {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            queue = self
+                .shared
+                .ready
+                .wait_timeout(queue, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Receive an item if one is available, without blocking.
+    ///
+    /// # Errors
+    /// - [`TryRecvError::Empty`] if no item is queued.
+    /// - [`TryRecvError::Disconnected`] if the queue is empty and the sender has been
+    ///   dropped.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut queue = self.shared.lock();
+        match queue.items.pop_front() {
+            Some(item) => Ok(item),
+            None if queue.sender_alive => Err(TryRecvError::Empty),
+            None => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    /// The number of items dropped so far because the channel was full.
+    pub fn dropped(&self) -> u64 {
+        self.shared.lock().dropped
+    }
+
+    /// An iterator that blocks waiting for items, and ends once the sender is dropped.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+}
+
+impl<T> Drop for StreamReceiver<T> {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.receiver_alive = false;
+        queue.items.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn drops_oldest() {
+        let (tx, rx) = stream_channel(2);
+        for i in 0..5 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(rx.dropped(), 3);
+        assert_eq!(rx.try_recv(), Ok(3));
+        assert_eq!(rx.try_recv(), Ok(4));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Timeout)
+        );
+
+        // queued items are delivered after the sender hangs up
+        tx.send(5).unwrap();
+        drop(tx);
+        assert_eq!(rx.recv(), Ok(5));
+        assert_eq!(rx.recv(), Err(RecvError));
+
+        let (tx, rx) = stream_channel(1);
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(1), Err(SendError(1)));
+    }
+}
@@ -0,0 +1,172 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use refimage::ImageProps;
+
+use generic_camera::client::GenCamClient;
+use generic_camera::dummy::{DummyPattern, GenCamDriverDummy, GenCamDummy};
+use generic_camera::server::{
+    FRAME_HEADER_LEN, GenCamServer, GenSrvCmd, GenSrvOutput, GenSrvValue, decode_frame,
+    encode_frame,
+};
+use generic_camera::{
+    Capture, GenCam, GenCamDriver, GenCamError, GenCamRoi, GenericImageOwned, PropertyValue,
+};
+
+fn start_server() -> (TcpStream, u32) {
+    let mut server = GenCamServer::default();
+    let id = server
+        .add_camera(GenCamDriverDummy::default().connect_first_device().unwrap())
+        .unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || server.serve(listener));
+    (TcpStream::connect(addr).unwrap(), id)
+}
+
+fn read_reply(stream: &mut TcpStream) -> GenSrvOutput {
+    let mut header = [0u8; FRAME_HEADER_LEN];
+    stream.read_exact(&mut header).unwrap();
+    let len = u32::from_le_bytes(header) as usize;
+    let mut frame = header.to_vec();
+    frame.resize(FRAME_HEADER_LEN + len, 0);
+    stream.read_exact(&mut frame[FRAME_HEADER_LEN..]).unwrap();
+    decode_frame(&frame).unwrap()
+}
+
+fn call(stream: &mut TcpStream, id: u32, cmd: GenSrvCmd) -> GenSrvOutput {
+    stream.write_all(&encode_frame(&(id, cmd))).unwrap();
+    read_reply(stream)
+}
+
+#[test]
+fn serve_dummy_over_tcp() {
+    let (mut stream, id) = start_server();
+    let Ok(GenSrvValue::Info(info)) = call(&mut stream, id, GenSrvCmd::Info) else {
+        panic!("Expected camera info");
+    };
+    assert_eq!(info.vendor, "Dummy");
+
+    let roi = GenCamRoi {
+        x_min: 0,
+        y_min: 0,
+        width: 640,
+        height: 480,
+    };
+    assert!(matches!(
+        call(&mut stream, id, GenSrvCmd::SetRoi(roi)),
+        Ok(GenSrvValue::Roi(r)) if r == roi
+    ));
+
+    let Ok(GenSrvValue::Image(img)) = call(&mut stream, id, GenSrvCmd::Capture) else {
+        panic!("Expected an image");
+    };
+    assert_eq!((img.width(), img.height()), (640, 480));
+
+    assert_eq!(
+        call(&mut stream, id.wrapping_add(1), GenSrvCmd::GetRoi).unwrap_err(),
+        GenCamError::InvalidId(id.wrapping_add(1) as _)
+    );
+}
+
+#[test]
+fn info_field() {
+    let (mut stream, id) = start_server();
+    assert!(matches!(
+        call(&mut stream, id, GenSrvCmd::GetInfoField("Interface".into())),
+        Ok(GenSrvValue::Property { value: PropertyValue::EnumStr(s), auto: None }) if s == "Aether"
+    ));
+    assert_eq!(
+        call(&mut stream, id, GenSrvCmd::GetInfoField("Missing".into())).unwrap_err(),
+        GenCamError::InfoFieldNotFound("Missing".into())
+    );
+}
+
+#[test]
+fn malformed_request() {
+    let (mut stream, id) = start_server();
+    stream.write_all(&[2, 0, 0, 0, 0xff, 0xff]).unwrap();
+    assert!(matches!(
+        read_reply(&mut stream),
+        Err(GenCamError::InvalidFormat(_))
+    ));
+    // the connection remains usable
+    assert!(matches!(
+        call(&mut stream, id, GenSrvCmd::GetRoi),
+        Ok(GenSrvValue::Roi(_))
+    ));
+}
+
+#[test]
+fn client_capture() {
+    let mut server = GenCamServer::default();
+    let id = server
+        .add_camera(GenCamDriverDummy::default().connect_first_device().unwrap())
+        .unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || server.serve(listener));
+
+    let mut client = GenCamClient::connect(addr, id).unwrap();
+    assert_eq!(client.vendor(), "Dummy");
+    assert!(client.info_handle().is_none());
+    client.set_exposure(Duration::from_millis(10)).unwrap();
+    assert_eq!(client.get_exposure(), Ok(Duration::from_millis(10)));
+    client.capture().unwrap();
+    assert!(client.camera_state().is_ok());
+    // server-side errors are passed through
+    assert!(matches!(
+        client.set_roi(&GenCamRoi {
+            x_min: 1920,
+            y_min: 0,
+            width: 1,
+            height: 1,
+        }),
+        Err(GenCamError::OutOfBounds(_))
+    ));
+    assert!(GenCamClient::connect(addr, id.wrapping_add(1)).is_err());
+}
+
+#[test]
+fn client_rejects_oversized_reply() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        stream.read_exact(&mut header).unwrap();
+        let mut request = vec![0u8; u32::from_le_bytes(header) as usize];
+        stream.read_exact(&mut request).unwrap();
+        // claim a reply the client must not allocate
+        stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+    });
+    assert_eq!(
+        GenCamClient::connect(addr, 0).unwrap_err(),
+        GenCamError::InvalidSize(u32::MAX as usize)
+    );
+}
+
+#[test]
+fn in_process_client() {
+    let make_camera = || {
+        let mut camera = GenCamDummy::with_seed(42);
+        camera.set_pattern(DummyPattern::Gradient);
+        camera.set_exposure(Duration::from_millis(5)).unwrap();
+        camera
+    };
+    let mut server = GenCamServer::default();
+    let id = server.add_camera(Box::new(make_camera())).unwrap();
+    let mut client = GenCamClient::in_process(server, id).unwrap();
+    assert_eq!(client.id(), id);
+    assert_eq!(client.vendor(), "Dummy");
+    assert_eq!(client.get_exposure(), Ok(Duration::from_millis(5)));
+
+    let mut direct = make_camera();
+    let expected = GenericImageOwned::from(direct.capture().unwrap());
+    let image = client.capture_owned().unwrap();
+    assert_eq!(image.get_image(), expected.get_image());
+    let image = GenericImageOwned::from(client.capture().unwrap());
+    assert_eq!(image.get_image(), expected.get_image());
+    assert!(GenCamClient::in_process(GenCamServer::default(), id).is_err());
+}
@@ -77,7 +77,8 @@ pub(crate) fn get_asi_devs() -> Result<Vec<GenCamDescriptor>, AsiError> {
         }
         let sn = get_sn(dev.CameraID).unwrap_or("Unknown".into());
         let mut dev: GenCamDescriptor = CameraInfo { raw: dev }.into();
-        dev.info.insert("Serial Number".to_string(), sn.into());
+        dev.info
+            .insert(GenCamDescriptor::SERIAL_NUMBER.to_string(), sn.into());
         devs.push(dev);
     }
     Ok(devs)